@@ -0,0 +1,96 @@
+//! The board file: the JSON the web app exports.
+
+use burner_list_core::{format, DataBase};
+use clap::ValueEnum;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub fn default_path() -> PathBuf {
+    if let Some(path) = std::env::var_os("BURNER_FILE") {
+        return PathBuf::from(path);
+    }
+    let home = std::env::var_os("HOME")
+        .map(PathBuf::from)
+        .unwrap_or_default();
+    home.join(".burner-list.json")
+}
+
+/// Reads the board at `path`. A missing file is an empty board.
+pub fn load(path: &Path) -> Result<DataBase, String> {
+    if !path.exists() {
+        return Ok(DataBase::default());
+    }
+    let raw = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    serde_json::from_str(&raw).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+pub fn save(path: &Path, db: &DataBase) -> Result<(), String> {
+    write(path, &to_json(db)?)
+}
+
+/// Writes a temporary file first so that a failed write keeps the old board.
+pub fn write(path: &Path, contents: &str) -> Result<(), String> {
+    let tmp = path.with_extension("tmp");
+    std::fs::write(&tmp, contents)
+        .and_then(|_| std::fs::rename(&tmp, path))
+        .map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+pub fn to_json(db: &DataBase) -> Result<String, String> {
+    serde_json::to_string_pretty(db).map_err(|e| e.to_string())
+}
+
+/// Formats of `export` and `import`, the same as the web app's.
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Format {
+    Json,
+    Markdown,
+    Todotxt,
+    Ical,
+    Csv,
+}
+
+impl Format {
+    /// Guesses the format from the extension of `path`, defaulting to JSON.
+    pub fn from_path(path: &Path) -> Self {
+        let extension = path
+            .extension()
+            .map(|extension| extension.to_string_lossy().to_ascii_lowercase())
+            .unwrap_or_default();
+        match extension.as_str() {
+            "md" | "markdown" => Format::Markdown,
+            "txt" => Format::Todotxt,
+            "ics" | "ical" => Format::Ical,
+            "csv" => Format::Csv,
+            _ => Format::Json,
+        }
+    }
+
+    pub fn export(self, db: &DataBase) -> Result<String, String> {
+        match self {
+            Format::Json => to_json(db),
+            Format::Markdown => Ok(format::to_markdown(db)),
+            Format::Todotxt => Ok(format::to_todotxt(db)),
+            Format::Ical => Ok(format::to_ical(db)),
+            Format::Csv => Ok(format::to_csv(db, [])),
+        }
+    }
+
+    pub fn import(self, text: &str) -> Result<DataBase, String> {
+        match self {
+            Format::Json => serde_json::from_str(text).map_err(|e| e.to_string()),
+            Format::Markdown => format::from_markdown(text, now()),
+            Format::Todotxt => format::from_todotxt(text, now()),
+            Format::Ical => format::from_ical(text, now()),
+            Format::Csv => format::from_csv(text, now()),
+        }
+    }
+}
+
+/// Milliseconds since the UNIX epoch, like `Date.now()` in the browser.
+pub fn now() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as f64)
+        .unwrap_or(0.0)
+}
@@ -0,0 +1,248 @@
+//! `burner`: reads and writes a board saved as the same JSON the web app
+//! exports, applying every change with `DataBase::apply_event`.
+
+mod file;
+mod tui;
+
+use burner_list_core::*;
+use clap::{Parser, Subcommand};
+use file::*;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use uuid::Uuid;
+
+#[derive(Parser)]
+#[command(name = "burner", version, about = "Burner List on the command line")]
+struct Cli {
+    /// Board file. Defaults to `$BURNER_FILE`, then `~/.burner-list.json`.
+    #[arg(short, long, global = true)]
+    file: Option<PathBuf>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Adds a task at the end of a rank.
+    Add {
+        #[arg(short, long, default_value = "primary")]
+        rank: TaskRank,
+        #[arg(required = true)]
+        text: Vec<String>,
+    },
+    /// Lists the tasks with their index and id.
+    List {
+        /// Lists only this rank.
+        #[arg(short, long)]
+        rank: Option<TaskRank>,
+    },
+    /// Moves a task to another rank and/or index.
+    Move {
+        /// Id of the task, or a unique prefix of it.
+        id: String,
+        /// Defaults to the current rank.
+        #[arg(short, long)]
+        rank: Option<TaskRank>,
+        /// Defaults to the end of the rank.
+        #[arg(short, long)]
+        index: Option<usize>,
+    },
+    /// Replaces the text of a task.
+    Edit {
+        id: String,
+        #[arg(required = true)]
+        text: Vec<String>,
+    },
+    /// Deletes a task.
+    Delete { id: String },
+    /// Marks a task as done.
+    Done { id: String },
+    /// Marks a task as not done.
+    Undone { id: String },
+    /// Removes every task in a rank.
+    Burn {
+        #[arg(short, long, default_value = "primary")]
+        rank: TaskRank,
+        /// Keeps the unfinished tasks.
+        #[arg(long)]
+        carry_over: bool,
+    },
+    /// Exchanges the tasks of two ranks.
+    Swap {
+        #[arg(default_value = "primary")]
+        rank1: TaskRank,
+        #[arg(default_value = "secondary")]
+        rank2: TaskRank,
+    },
+    /// Writes the board, to stdout by default.
+    Export {
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Defaults to the extension of the output, then JSON.
+        #[arg(long, value_enum)]
+        format: Option<Format>,
+    },
+    /// Merges a board exported by the web app or `export` into this one.
+    Import {
+        input: PathBuf,
+        /// Defaults to the extension of the input, then JSON.
+        #[arg(long, value_enum)]
+        format: Option<Format>,
+        /// Replaces the tasks instead of merging.
+        #[arg(long)]
+        replace: bool,
+    },
+    /// Opens the board in an interactive terminal UI.
+    Tui,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    match run(cli) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("burner: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(cli: Cli) -> Result<(), String> {
+    let path = cli.file.unwrap_or_else(default_path);
+    let mut db = load(&path)?;
+
+    let event = match cli.command {
+        Command::Add { rank, text } => {
+            let id = Uuid::new_v4();
+            apply(
+                &mut db,
+                TaskEvent::Add(TaskAddData {
+                    id,
+                    rank,
+                    value: text.join(" "),
+                }),
+            )?;
+            println!("{}", short_id(id));
+            return save(&path, &db);
+        }
+        Command::List { rank } => {
+            let ranks = rank
+                .map(|rank| vec![rank])
+                .unwrap_or(TaskRank::ALL.to_vec());
+            print_tasks(&db, &ranks);
+            return Ok(());
+        }
+        Command::Export { output, format } => {
+            let format = format
+                .or_else(|| output.as_deref().map(Format::from_path))
+                .unwrap_or(Format::Json);
+            let text = format.export(&db)?;
+            return match output {
+                Some(output) => write(&output, &text),
+                None => {
+                    println!("{}", text.trim_end());
+                    Ok(())
+                }
+            };
+        }
+        Command::Import {
+            input,
+            format,
+            replace,
+        } => {
+            let format = format.unwrap_or_else(|| Format::from_path(&input));
+            let text = std::fs::read_to_string(&input)
+                .map_err(|e| format!("{}: {}", input.display(), e))?;
+            let imported = format
+                .import(&text)
+                .map_err(|e| format!("{}: {}", input.display(), e))?;
+            let target = if replace {
+                imported
+            } else {
+                let mut merged = db.clone();
+                merged.merge(&imported);
+                merged
+            };
+            db.revert(&target, now());
+            return save(&path, &db);
+        }
+        Command::Tui => return tui::run(&path, db),
+        Command::Move { id, rank, index } => {
+            let task = find_task(&db, &id)?;
+            TaskEvent::Move(TaskMoveData {
+                id: task.id(),
+                rank: rank.unwrap_or(task.rank),
+                index,
+            })
+        }
+        Command::Edit { id, text } => TaskEvent::Edit(TaskEditData {
+            id: find_task(&db, &id)?.id(),
+            value: text.join(" "),
+        }),
+        Command::Delete { id } => TaskEvent::Delete(TaskDeleteData {
+            id: find_task(&db, &id)?.id(),
+        }),
+        Command::Done { id } => TaskEvent::Complete(TaskCompleteData {
+            id: find_task(&db, &id)?.id(),
+        }),
+        Command::Undone { id } => TaskEvent::Uncomplete(TaskUncompleteData {
+            id: find_task(&db, &id)?.id(),
+        }),
+        Command::Burn { rank, carry_over } => {
+            let burned = db.get_burnable(rank, carry_over).len();
+            println!("burned {} task(s)", burned);
+            if burned == 0 {
+                return Ok(());
+            }
+            apply(&mut db, TaskEvent::Burn(TaskBurnData { rank, carry_over }))?;
+            return save(&path, &db);
+        }
+        Command::Swap { rank1, rank2 } => TaskEvent::Swap(TaskSwapData { rank1, rank2 }),
+    };
+
+    apply(&mut db, event)?;
+    save(&path, &db)
+}
+
+fn apply(db: &mut DataBase, event: TaskEvent) -> Result<(), String> {
+    if db.apply_event(&event, now()) {
+        Ok(())
+    } else {
+        Err("nothing changed".to_owned())
+    }
+}
+
+/// Finds the task whose id starts with `prefix`.
+fn find_task<'a>(db: &'a DataBase, prefix: &str) -> Result<&'a TaskData, String> {
+    let prefix = prefix.to_ascii_lowercase();
+    let mut found = db
+        .tasks()
+        .filter(|task| task.id().to_string().starts_with(&prefix));
+    match (found.next(), found.next()) {
+        (Some(task), None) => Ok(task),
+        (None, _) => Err(format!("no task matches `{}`", prefix)),
+        (Some(_), Some(_)) => Err(format!("`{}` matches more than one task", prefix)),
+    }
+}
+
+fn short_id(id: Uuid) -> String {
+    id.to_string()[..8].to_owned()
+}
+
+fn print_tasks(db: &DataBase, ranks: &[TaskRank]) {
+    for rank in ranks {
+        println!("{}", rank);
+        for (index, task) in db.get_by_rank(*rank).into_iter().enumerate() {
+            let check = if task.done { "x" } else { " " };
+            let prefix = format!("{:>3} [{}] {}  ", index, check, short_id(task.id()));
+            let mut lines = task.value.lines();
+            println!("{}{}", prefix, lines.next().unwrap_or_default());
+            // continuation lines line up with the first one
+            let indent = " ".repeat(prefix.chars().count());
+            for line in lines {
+                println!("{}{}", indent, line);
+            }
+        }
+    }
+}
@@ -0,0 +1,360 @@
+//! `burner tui`: the three columns of the web app in the terminal, Primary on
+//! the left and Secondary above Other on the right.
+
+use crate::file::{now, save};
+use burner_list_core::*;
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers};
+use crossterm::execute;
+use crossterm::terminal::{
+    disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
+};
+use std::io::Stdout;
+use std::path::{Path, PathBuf};
+use tui::backend::CrosstermBackend;
+use tui::layout::{Constraint, Direction, Layout, Rect};
+use tui::style::{Color, Modifier, Style};
+use tui::text::{Span, Spans};
+use tui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph};
+use tui::{Frame, Terminal};
+use uuid::Uuid;
+
+const HELP: &str = "↑↓←→ select  Shift+↑↓←→ move  a add  Enter edit  Space done  d delete  b burn  B burn (carry over)  s swap  q quit";
+
+type Backend = CrosstermBackend<Stdout>;
+
+pub fn run(path: &Path, db: DataBase) -> Result<(), String> {
+    enable_raw_mode().map_err(|e| e.to_string())?;
+    let mut stdout = std::io::stdout();
+    execute!(stdout, EnterAlternateScreen).map_err(|e| e.to_string())?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(stdout)).map_err(|e| e.to_string())?;
+
+    let mut app = TuiApp::new(path, db);
+    let result = app.run(&mut terminal);
+
+    // restore the terminal even if the app failed
+    let _ = disable_raw_mode();
+    let _ = execute!(terminal.backend_mut(), LeaveAlternateScreen);
+    let _ = terminal.show_cursor();
+    result
+}
+
+/// A task being written. `id` is `None` for a new task.
+struct Editor {
+    id: Option<Uuid>,
+    rank: TaskRank,
+    text: String,
+}
+
+struct TuiApp {
+    path: PathBuf,
+    db: DataBase,
+    focus: TaskRank,
+    /// Selected index of each column, in the order of `TaskRank::ALL`.
+    selected: [usize; 3],
+    editor: Option<Editor>,
+    message: Option<String>,
+}
+
+impl TuiApp {
+    fn new(path: &Path, db: DataBase) -> Self {
+        Self {
+            path: path.to_owned(),
+            db,
+            focus: TaskRank::Primary,
+            selected: [0; 3],
+            editor: None,
+            message: None,
+        }
+    }
+
+    fn run(&mut self, terminal: &mut Terminal<Backend>) -> Result<(), String> {
+        loop {
+            terminal.draw(|f| self.draw(f)).map_err(|e| e.to_string())?;
+
+            if let Event::Key(key) = event::read().map_err(|e| e.to_string())? {
+                self.message = None;
+                // raw mode turns Ctrl+C into a key instead of a signal
+                let interrupt =
+                    key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL);
+                let quit = if interrupt {
+                    true
+                } else if self.editor.is_some() {
+                    self.edit_key(key);
+                    false
+                } else {
+                    self.key(key)
+                };
+                if quit {
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    /// Handles a key outside the editor. Returns whether to quit.
+    fn key(&mut self, key: KeyEvent) -> bool {
+        let shift = key.modifiers.contains(KeyModifiers::SHIFT);
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return true,
+            KeyCode::Char('K') => self.move_within(-1),
+            KeyCode::Char('J') => self.move_within(1),
+            KeyCode::Char('H') => self.move_across(-1),
+            KeyCode::Char('L') => self.move_across(1),
+            KeyCode::Up if shift => self.move_within(-1),
+            KeyCode::Down if shift => self.move_within(1),
+            KeyCode::Left if shift => self.move_across(-1),
+            KeyCode::Right if shift => self.move_across(1),
+            KeyCode::Up | KeyCode::Char('k') => self.select(-1),
+            KeyCode::Down | KeyCode::Char('j') => self.select(1),
+            KeyCode::Left | KeyCode::Char('h') => self.focus = TaskRank::Primary,
+            KeyCode::Right | KeyCode::Char('l') if self.focus == TaskRank::Primary => {
+                self.focus = TaskRank::Secondary
+            }
+            KeyCode::Tab => self.focus = neighbor(self.focus, 1),
+            KeyCode::BackTab => self.focus = neighbor(self.focus, -1),
+            KeyCode::Char('a') | KeyCode::Char('n') => {
+                self.editor = Some(Editor {
+                    id: None,
+                    rank: self.focus,
+                    text: String::new(),
+                })
+            }
+            KeyCode::Enter | KeyCode::Char('e') | KeyCode::Char('i') => {
+                if let Some(task) = self.selected_task() {
+                    self.editor = Some(Editor {
+                        id: Some(task.id()),
+                        rank: task.rank,
+                        text: task.value.clone(),
+                    });
+                }
+            }
+            KeyCode::Char(' ') | KeyCode::Char('x') => {
+                if let Some(task) = self.selected_task() {
+                    let id = task.id();
+                    self.apply(if task.done {
+                        TaskEvent::Uncomplete(TaskUncompleteData { id })
+                    } else {
+                        TaskEvent::Complete(TaskCompleteData { id })
+                    });
+                }
+            }
+            KeyCode::Char('d') | KeyCode::Delete => {
+                if let Some(task) = self.selected_task() {
+                    let id = task.id();
+                    self.apply(TaskEvent::Delete(TaskDeleteData { id }));
+                }
+            }
+            KeyCode::Char('b') | KeyCode::Char('B') => {
+                let carry_over = key.code == KeyCode::Char('B');
+                self.apply(TaskEvent::Burn(TaskBurnData {
+                    rank: TaskRank::Primary,
+                    carry_over,
+                }));
+            }
+            KeyCode::Char('s') => self.apply(TaskEvent::Swap(TaskSwapData {
+                rank1: TaskRank::Primary,
+                rank2: TaskRank::Secondary,
+            })),
+            KeyCode::Char(c @ '1'..='3') => {
+                let rank = TaskRank::ALL[c as usize - '1' as usize];
+                self.move_to(rank, None);
+            }
+            _ => {}
+        }
+        false
+    }
+
+    fn edit_key(&mut self, key: KeyEvent) {
+        let editor = match self.editor.as_mut() {
+            Some(editor) => editor,
+            None => return,
+        };
+        match key.code {
+            KeyCode::Esc => self.editor = None,
+            // Alt+Enter, since terminals cannot tell Shift+Enter from Enter
+            KeyCode::Enter if key.modifiers.contains(KeyModifiers::ALT) => editor.text.push('\n'),
+            KeyCode::Enter => {
+                if let Some(editor) = self.editor.take() {
+                    self.commit(editor);
+                }
+            }
+            KeyCode::Backspace => {
+                editor.text.pop();
+            }
+            KeyCode::Char(c) => editor.text.push(c),
+            _ => {}
+        }
+    }
+
+    /// Saves the edited text. Emptying a task deletes it, as in the web app.
+    fn commit(&mut self, editor: Editor) {
+        let value = editor.text.trim_end().to_owned();
+        match editor.id {
+            None if value.is_empty() => {}
+            None => {
+                self.apply(TaskEvent::Add(TaskAddData {
+                    id: Uuid::new_v4(),
+                    rank: editor.rank,
+                    value,
+                }));
+                let last = self.db.get_by_rank(editor.rank).len().saturating_sub(1);
+                self.selected[column(editor.rank)] = last;
+            }
+            Some(id) if value.is_empty() => self.apply(TaskEvent::Delete(TaskDeleteData { id })),
+            Some(id) => self.apply(TaskEvent::Edit(TaskEditData { id, value })),
+        }
+    }
+
+    fn apply(&mut self, event: TaskEvent) {
+        if !self.db.apply_event(&event, now()) {
+            return;
+        }
+        if let Err(e) = save(&self.path, &self.db) {
+            self.message = Some(e);
+        }
+        for rank in TaskRank::ALL {
+            let len = self.db.get_by_rank(rank).len();
+            let selected = &mut self.selected[column(rank)];
+            *selected = (*selected).min(len.saturating_sub(1));
+        }
+    }
+
+    fn selected_task(&self) -> Option<&TaskData> {
+        self.db
+            .get_by_rank(self.focus)
+            .get(self.selected[column(self.focus)])
+            .copied()
+    }
+
+    /// Moves the selection, continuing between Secondary and Other like the
+    /// columns are stacked.
+    fn select(&mut self, delta: isize) {
+        let len = self.db.get_by_rank(self.focus).len();
+        let selected = self.selected[column(self.focus)];
+        match (self.focus, delta) {
+            (TaskRank::Secondary, 1) if selected + 1 >= len => self.focus = TaskRank::Other,
+            (TaskRank::Other, -1) if selected == 0 => self.focus = TaskRank::Secondary,
+            _ => {
+                let selected = (selected as isize + delta).clamp(0, len.saturating_sub(1) as isize);
+                self.selected[column(self.focus)] = selected as usize;
+            }
+        }
+    }
+
+    fn move_within(&mut self, delta: isize) {
+        let selected = self.selected[column(self.focus)] as isize + delta;
+        let len = self.db.get_by_rank(self.focus).len() as isize;
+        if (0..len).contains(&selected) {
+            self.move_to(self.focus, Some(selected as usize));
+        }
+    }
+
+    fn move_across(&mut self, delta: isize) {
+        let rank = match (self.focus, delta) {
+            (TaskRank::Primary, 1) => TaskRank::Secondary,
+            (TaskRank::Secondary | TaskRank::Other, -1) => TaskRank::Primary,
+            _ => return,
+        };
+        self.move_to(rank, None);
+    }
+
+    /// Moves the selected task to `index` of `rank`, or to its end, and keeps
+    /// it selected.
+    fn move_to(&mut self, rank: TaskRank, index: Option<usize>) {
+        let id = match self.selected_task() {
+            Some(task) => task.id(),
+            None => return,
+        };
+        self.apply(TaskEvent::Move(TaskMoveData { id, rank, index }));
+        let tasks = self.db.get_by_rank(rank);
+        if let Some(i) = tasks.iter().position(|task| task.id() == id) {
+            self.focus = rank;
+            self.selected[column(rank)] = i;
+        }
+    }
+
+    fn draw(&self, f: &mut Frame<Backend>) {
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(3), Constraint::Length(3)])
+            .split(f.size());
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(rows[0]);
+        let right = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(columns[1]);
+
+        self.draw_column(f, TaskRank::Primary, columns[0]);
+        self.draw_column(f, TaskRank::Secondary, right[0]);
+        self.draw_column(f, TaskRank::Other, right[1]);
+
+        let footer = match (&self.editor, &self.message) {
+            (Some(editor), _) => Paragraph::new(format!("{}▏", editor.text)).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title("Enter: save  Alt+Enter: new line  Esc: cancel"),
+            ),
+            (None, Some(message)) => Paragraph::new(message.as_str())
+                .style(Style::default().fg(Color::Red))
+                .block(Block::default().borders(Borders::ALL)),
+            (None, None) => Paragraph::new(HELP).block(Block::default().borders(Borders::ALL)),
+        };
+        f.render_widget(footer, rows[1]);
+    }
+
+    fn draw_column(&self, f: &mut Frame<Backend>, rank: TaskRank, area: Rect) {
+        let focused = rank == self.focus;
+        let items = self
+            .db
+            .get_by_rank(rank)
+            .into_iter()
+            .map(|task| {
+                let style = if task.done {
+                    Style::default()
+                        .fg(Color::DarkGray)
+                        .add_modifier(Modifier::CROSSED_OUT)
+                } else {
+                    Style::default()
+                };
+                let lines = task
+                    .value
+                    .lines()
+                    .map(|line| Spans::from(Span::styled(line.to_owned(), style)))
+                    .collect::<Vec<_>>();
+                ListItem::new(lines)
+            })
+            .collect::<Vec<_>>();
+
+        let border = if focused {
+            Style::default().fg(Color::Yellow)
+        } else {
+            Style::default()
+        };
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(border)
+                    .title(rank.to_string()),
+            )
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+
+        let mut state = ListState::default();
+        if focused {
+            state.select(Some(self.selected[column(rank)]));
+        }
+        f.render_stateful_widget(list, area, &mut state);
+    }
+}
+
+fn column(rank: TaskRank) -> usize {
+    TaskRank::ALL.iter().position(|&r| r == rank).unwrap_or(0)
+}
+
+fn neighbor(rank: TaskRank, delta: isize) -> TaskRank {
+    let len = TaskRank::ALL.len() as isize;
+    TaskRank::ALL[(column(rank) as isize + delta).rem_euclid(len) as usize]
+}
@@ -0,0 +1,190 @@
+//! Runs the `burner` binary on a board file in a temporary directory.
+
+use burner_list_core::{DataBase, TaskRank};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use uuid::Uuid;
+
+/// A temporary directory with a board file in it, removed when dropped.
+struct Board {
+    dir: PathBuf,
+    file: PathBuf,
+}
+
+impl Board {
+    fn new() -> Self {
+        let dir = std::env::temp_dir().join(format!("burner-cli-{}", Uuid::new_v4()));
+        std::fs::create_dir(&dir).unwrap();
+        let file = dir.join("board.json");
+        Board { dir, file }
+    }
+
+    fn run(&self, args: &[&str]) -> Output {
+        Command::new(env!("CARGO_BIN_EXE_burner"))
+            .arg("--file")
+            .arg(&self.file)
+            .args(args)
+            .env_remove("BURNER_FILE")
+            .output()
+            .expect("burner to run")
+    }
+
+    /// Runs a command that must succeed and returns its stdout.
+    fn ok(&self, args: &[&str]) -> String {
+        let output = self.run(args);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(output.status.success(), "{:?}: {}", args, stderr);
+        String::from_utf8(output.stdout).unwrap()
+    }
+
+    /// Runs a command that must fail and returns its stderr.
+    fn fails(&self, args: &[&str]) -> String {
+        let output = self.run(args);
+        assert!(!output.status.success(), "{:?} succeeded", args);
+        String::from_utf8(output.stderr).unwrap()
+    }
+
+    /// Adds a task and returns its short id.
+    fn add(&self, rank: &str, text: &str) -> String {
+        let id = self.ok(&["add", "--rank", rank, text]).trim().to_owned();
+        assert_eq!(id.len(), 8, "{}", id);
+        id
+    }
+
+    fn path(&self, name: &str) -> PathBuf {
+        self.dir.join(name)
+    }
+
+    fn db(&self) -> DataBase {
+        load(&self.file)
+    }
+
+    fn raw(&self) -> String {
+        std::fs::read_to_string(&self.file).unwrap()
+    }
+}
+
+impl Drop for Board {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+fn load(path: &Path) -> DataBase {
+    serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap()
+}
+
+/// `(value, done)` of the tasks of `rank`, in order.
+fn tasks(db: &DataBase, rank: TaskRank) -> Vec<(String, bool)> {
+    db.get_by_rank(rank)
+        .into_iter()
+        .map(|task| (task.value.clone(), task.done))
+        .collect()
+}
+
+fn task(value: &str, done: bool) -> (String, bool) {
+    (value.to_owned(), done)
+}
+
+#[test]
+fn tasks_are_added_moved_and_finished() {
+    let board = Board::new();
+    let first = board.add("primary", "first");
+    let second = board.add("secondary", "second");
+    board.add("primary", "third");
+
+    let db = board.db();
+    assert_eq!(
+        tasks(&db, TaskRank::Primary),
+        [task("first", false), task("third", false)]
+    );
+    assert_eq!(tasks(&db, TaskRank::Secondary), [task("second", false)]);
+
+    board.ok(&["move", &first, "--rank", "other"]);
+    board.ok(&["move", &second, "--rank", "primary", "--index", "0"]);
+    board.ok(&["done", &first]);
+
+    let db = board.db();
+    assert_eq!(
+        tasks(&db, TaskRank::Primary),
+        [task("second", false), task("third", false)]
+    );
+    assert_eq!(tasks(&db, TaskRank::Other), [task("first", true)]);
+
+    let list = board.ok(&["list", "--rank", "other"]);
+    assert_eq!(list, format!("Other\n  0 [x] {}  first\n", first));
+}
+
+#[test]
+fn burn_and_swap_change_the_ranks() {
+    let board = Board::new();
+    let done = board.add("primary", "done");
+    board.add("primary", "open");
+    board.add("secondary", "later");
+    board.ok(&["done", &done]);
+
+    assert_eq!(board.ok(&["burn", "--carry-over"]), "burned 1 task(s)\n");
+    assert_eq!(tasks(&board.db(), TaskRank::Primary), [task("open", false)]);
+
+    // burning an empty rank leaves the file alone
+    let before = board.raw();
+    assert_eq!(board.ok(&["burn", "--rank", "other"]), "burned 0 task(s)\n");
+    assert_eq!(board.raw(), before);
+
+    board.ok(&["swap", "primary", "secondary"]);
+    let db = board.db();
+    assert_eq!(tasks(&db, TaskRank::Primary), [task("later", false)]);
+    assert_eq!(tasks(&db, TaskRank::Secondary), [task("open", false)]);
+}
+
+#[test]
+fn boards_are_exported_and_imported() {
+    let board = Board::new();
+    let first = board.add("primary", "first");
+    board.add("other", "second");
+    board.ok(&["done", &first]);
+
+    let markdown = board.ok(&["export", "--format", "markdown"]);
+    assert!(markdown.contains("- [x] first\n"), "{}", markdown);
+    let todotxt = board.path("board.txt");
+    board.ok(&["export", "--output", todotxt.to_str().unwrap()]);
+    assert!(std::fs::read_to_string(&todotxt).unwrap().contains("(C) "));
+
+    // into another board, merged with what it has
+    let other = Board::new();
+    other.add("secondary", "kept");
+    other.ok(&["import", todotxt.to_str().unwrap()]);
+    let db = other.db();
+    assert_eq!(tasks(&db, TaskRank::Primary), [task("first", true)]);
+    assert_eq!(tasks(&db, TaskRank::Secondary), [task("kept", false)]);
+    assert_eq!(tasks(&db, TaskRank::Other), [task("second", false)]);
+
+    // or replacing it, keeping the ids of a JSON export
+    let json = board.path("export.json");
+    board.ok(&["export", "--output", json.to_str().unwrap()]);
+    other.ok(&["import", "--replace", json.to_str().unwrap()]);
+    let db = other.db();
+    assert!(tasks(&db, TaskRank::Secondary).is_empty());
+    let ids = |db: &DataBase| {
+        let mut ids = db.tasks().map(|task| task.id()).collect::<Vec<_>>();
+        ids.sort();
+        ids
+    };
+    assert_eq!(ids(&db), ids(&board.db()));
+}
+
+#[test]
+fn unknown_ids_and_ranks_are_errors() {
+    let board = Board::new();
+    board.add("primary", "task");
+    let before = board.raw();
+
+    let stderr = board.fails(&["done", "ffffffff"]);
+    assert_eq!(stderr, "burner: no task matches `ffffffff`\n");
+    let stderr = board.fails(&["add", "--rank", "someday", "task"]);
+    assert!(stderr.contains("someday"), "{}", stderr);
+    let stderr = board.fails(&["move", "0", "--rank", "someday"]);
+    assert!(stderr.contains("someday"), "{}", stderr);
+
+    assert_eq!(board.raw(), before);
+}
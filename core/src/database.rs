@@ -0,0 +1,452 @@
+mod impl_serde;
+mod migration;
+
+use crate::{position, *};
+use im::{HashMap, Vector};
+use slab::Slab;
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct DataBase {
+    store: Slab<TaskData>,
+    id_index: HashMap<Uuid, usize>,
+    /// Tasks of each rank, sorted by position.
+    rank_index: HashMap<TaskRank, Vector<usize>>,
+    /// Deleted and burned tasks, with the stamp of their removal.
+    tombstones: HashMap<Uuid, Stamp>,
+    /// Replica issuing the stamps of this copy of the board.
+    replica: Uuid,
+    /// Latest stamp issued or merged.
+    clock: Stamp,
+    hash: Uuid,
+}
+
+impl Default for DataBase {
+    fn default() -> Self {
+        Self {
+            store: Slab::default(),
+            id_index: HashMap::default(),
+            rank_index: HashMap::default(),
+            tombstones: HashMap::default(),
+            replica: Uuid::new_v4(),
+            clock: Stamp::default(),
+            hash: Uuid::new_v4(),
+        }
+    }
+}
+
+impl PartialEq for DataBase {
+    fn eq(&self, other: &Self) -> bool {
+        self.hash == other.hash
+    }
+}
+
+impl core::fmt::Debug for DataBase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let store = self
+            .store
+            .iter()
+            .map(move |(i, task)| format!("({}, {:?})", i, task))
+            .collect::<Vec<_>>();
+        f.debug_struct("DataBase")
+            .field("hash", &self.hash)
+            .field("replica", &self.replica)
+            .field("clock", &self.clock)
+            .field("_store", &store)
+            .finish()
+    }
+}
+
+impl DataBase {
+    /// Applies `event` that happened at `timestamp` (milliseconds since the
+    /// UNIX epoch). Returns whether the database changed.
+    pub fn apply_event(&mut self, event: &TaskEvent, timestamp: f64) -> bool {
+        match event {
+            TaskEvent::Add(TaskAddData { id, rank, value }) => {
+                if self.id_index.contains_key(id) || self.tombstones.contains_key(id) {
+                    return false;
+                }
+
+                let stamp = self.tick(timestamp);
+                let mut task = TaskData::with_id(*id, *rank, value);
+                task.created_at = Some(timestamp);
+                task.edited_at = Some(timestamp);
+                task.moved_at = Some(timestamp);
+                task.position = self.position_at(*rank, None);
+                task.stamps = TaskStamps {
+                    value: stamp,
+                    rank: stamp,
+                    done: stamp,
+                    restored: Stamp::default(),
+                };
+                self.add(task);
+                return true;
+            }
+            TaskEvent::Edit(TaskEditData { id, value }) => {
+                if let Some(&i) = self.id_index.get(id) {
+                    let stamp = self.tick(timestamp);
+                    let task = &mut self.store[i];
+                    task.value = value.clone();
+                    task.edited_at = Some(timestamp);
+                    task.stamps.value = stamp;
+                    self.hash = Uuid::new_v4();
+                    return true;
+                }
+            }
+            &TaskEvent::Move(TaskMoveData { id, rank, index }) => {
+                if let Some(mut task) = self.remove_by_id(id) {
+                    if task.rank != rank {
+                        task.rank = rank;
+                        task.moved_at = Some(timestamp);
+                    }
+                    task.position = self.position_at(rank, index);
+                    task.stamps.rank = self.tick(timestamp);
+                    self.add(task);
+                    return true;
+                }
+            }
+            &TaskEvent::Delete(TaskDeleteData { id }) => {
+                if self.remove_by_id(id).is_some() {
+                    let stamp = self.tick(timestamp);
+                    self.tombstones.insert(id, stamp);
+                    return true;
+                }
+            }
+            &TaskEvent::Complete(TaskCompleteData { id }) => {
+                return self.set_done(id, Some(timestamp), timestamp);
+            }
+            &TaskEvent::Uncomplete(TaskUncompleteData { id }) => {
+                return self.set_done(id, None, timestamp);
+            }
+            &TaskEvent::Burn(TaskBurnData { rank, carry_over }) => {
+                return self.burn_tasks(rank, carry_over, timestamp);
+            }
+            &TaskEvent::Swap(TaskSwapData { rank1, rank2 }) => {
+                return self.swap_tasks(rank1, rank2, timestamp);
+            }
+        }
+
+        false
+    }
+
+    /// Removes the tasks in `rank`. With `carry_over`, unfinished tasks stay
+    /// for the next page. Returns whether any task was removed.
+    pub fn burn_tasks(&mut self, rank: TaskRank, carry_over: bool, timestamp: f64) -> bool {
+        let task_ids = self
+            .get_burnable(rank, carry_over)
+            .iter()
+            .map(move |task| task.id())
+            .collect::<Vec<_>>();
+        if task_ids.is_empty() {
+            return false;
+        }
+
+        let stamp = self.tick(timestamp);
+        for id in task_ids {
+            self.remove_by_id(id);
+            self.tombstones.insert(id, stamp);
+        }
+        true
+    }
+
+    pub fn get_burnable(&self, rank: TaskRank, carry_over: bool) -> Vec<&TaskData> {
+        let mut tasks = self.get_by_rank(rank);
+        if carry_over {
+            tasks.retain(|task| task.done);
+        }
+        tasks
+    }
+
+    /// Exchanges the tasks of two ranks, keeping their order. Returns whether
+    /// any task was moved.
+    pub fn swap_tasks(&mut self, rank1: TaskRank, rank2: TaskRank, timestamp: f64) -> bool {
+        if rank1 == rank2 {
+            return false;
+        }
+        let task_ids = self
+            .get_by_rank(rank1)
+            .into_iter()
+            .chain(self.get_by_rank(rank2))
+            .map(move |task| task.id())
+            .collect::<Vec<_>>();
+        if task_ids.is_empty() {
+            return false;
+        }
+
+        let stamp = self.tick(timestamp);
+        for id in task_ids {
+            if let Some(mut task) = self.remove_by_id(id) {
+                task.rank = if task.rank == rank1 { rank2 } else { rank1 };
+                task.moved_at = Some(timestamp);
+                task.stamps.rank = stamp;
+                self.add(task);
+            }
+        }
+        true
+    }
+
+    /// Merges a copy of the board edited by another replica. Merging is
+    /// commutative, associative and idempotent, so every replica that has
+    /// merged the same copies ends up with the same tasks in the same order.
+    ///
+    /// Each field of a task is a last-writer-wins register ordered by its
+    /// `Stamp`, and a deleted task stays deleted even if another replica
+    /// edited it concurrently, unless it was restored after the deletion.
+    pub fn merge(&mut self, other: &DataBase) {
+        for (&id, &stamp) in other.tombstones.iter() {
+            let removed = self.tombstones.entry(id).or_insert(stamp);
+            *removed = (*removed).max(stamp);
+            let removed = *removed;
+            if let Some(task) = self.get_by_id(id) {
+                if task.stamps.restored <= removed {
+                    self.remove_by_id(id);
+                }
+            }
+        }
+
+        for (_, task) in other.store.iter() {
+            if let Some(&removed) = self.tombstones.get(&task.id()) {
+                if task.stamps.restored <= removed {
+                    continue;
+                }
+            }
+            match self.remove_by_id(task.id()) {
+                Some(mut mine) => {
+                    mine.merge(task);
+                    self.add(mine);
+                }
+                None => self.add(task.clone()),
+            }
+        }
+
+        self.clock = self.clock.max(other.clock);
+        self.hash = Uuid::new_v4();
+    }
+
+    /// Turns the database into a copy of `target`, usually an earlier state of
+    /// it, by writing the differences as new changes, so that undo, redo and
+    /// imports merge like any other edit. Tasks deleted since then are
+    /// restored.
+    pub fn revert(&mut self, target: &DataBase, timestamp: f64) {
+        let stamp = self.tick(timestamp);
+        let removed = self
+            .store
+            .iter()
+            .map(|(_, task)| task.id())
+            .filter(|id| !target.id_index.contains_key(id))
+            .collect::<Vec<_>>();
+        for id in removed {
+            self.remove_by_id(id);
+            self.tombstones.insert(id, stamp);
+        }
+
+        for (_, theirs) in target.store.iter() {
+            let task = match self.remove_by_id(theirs.id()) {
+                Some(mut task) => {
+                    if task.value != theirs.value {
+                        task.value = theirs.value.clone();
+                        task.edited_at = theirs.edited_at;
+                        task.stamps.value = stamp;
+                    }
+                    if task.rank != theirs.rank || task.position != theirs.position {
+                        task.rank = theirs.rank;
+                        task.position = theirs.position.clone();
+                        task.moved_at = theirs.moved_at;
+                        task.stamps.rank = stamp;
+                    }
+                    if task.done != theirs.done {
+                        task.done = theirs.done;
+                        task.completed_at = theirs.completed_at;
+                        task.stamps.done = stamp;
+                    }
+                    task
+                }
+                None => {
+                    let mut task = theirs.clone();
+                    let restored = if self.tombstones.contains_key(&theirs.id()) {
+                        stamp
+                    } else {
+                        theirs.stamps.restored
+                    };
+                    task.stamps = TaskStamps {
+                        value: stamp,
+                        rank: stamp,
+                        done: stamp,
+                        restored,
+                    };
+                    task
+                }
+            };
+            self.add(task);
+        }
+    }
+
+    /// The tasks and tombstones written after `since`, or all of them without
+    /// it, as a copy that `merge` can apply to another replica. `None` if
+    /// nothing was written.
+    pub fn changes_since(&self, since: Option<Stamp>) -> Option<DataBase> {
+        let changed = |stamp: &Stamp| match since {
+            Some(since) => *stamp > since,
+            None => true,
+        };
+
+        let mut changes = DataBase {
+            replica: self.replica,
+            clock: self.clock,
+            ..Default::default()
+        };
+        for (_, task) in self.store.iter() {
+            let TaskStamps {
+                value,
+                rank,
+                done,
+                restored,
+            } = &task.stamps;
+            if changed(value) || changed(rank) || changed(done) || changed(restored) {
+                changes.add(task.clone());
+            }
+        }
+        for (&id, stamp) in self.tombstones.iter() {
+            if changed(stamp) {
+                changes.tombstones.insert(id, *stamp);
+            }
+        }
+
+        if changes.store.is_empty() && changes.tombstones.is_empty() {
+            None
+        } else {
+            Some(changes)
+        }
+    }
+
+    /// Latest stamp issued or merged.
+    pub fn clock(&self) -> Stamp {
+        self.clock
+    }
+
+    pub fn tasks(&self) -> impl Iterator<Item = &TaskData> {
+        self.store.iter().map(|(_, task)| task)
+    }
+
+    /// Copy of the database without its tasks.
+    pub fn without_tasks(&self) -> DataBase {
+        DataBase {
+            tombstones: self.tombstones.clone(),
+            replica: self.replica,
+            clock: self.clock,
+            ..Default::default()
+        }
+    }
+
+    /// Puts back a task taken out by `without_tasks`, keeping its position.
+    /// A task without a position goes to the end of its rank.
+    pub fn insert_task(&mut self, task: TaskData) {
+        self.add(task);
+    }
+
+    /// Puts back `task`, deleted or burned earlier, into `rank` with its id
+    /// and timestamps: in its old place if `rank` is the one it was removed
+    /// from, or else at the end. The restore outlives the deletion on every
+    /// replica. Returns false if the task is on the board.
+    pub fn restore_task(&mut self, task: &TaskData, rank: TaskRank, timestamp: f64) -> bool {
+        if self.id_index.contains_key(&task.id()) {
+            return false;
+        }
+
+        let stamp = self.tick(timestamp);
+        let mut task = task.clone();
+        if task.rank != rank {
+            task.rank = rank;
+            task.moved_at = Some(timestamp);
+            task.position = self.position_at(rank, None);
+        }
+        task.stamps = TaskStamps {
+            value: stamp,
+            rank: stamp,
+            done: stamp,
+            restored: stamp,
+        };
+        self.add(task);
+        true
+    }
+
+    pub fn get_by_id(&self, id: Uuid) -> Option<&TaskData> {
+        Some(&self.store[*self.id_index.get(&id)?])
+    }
+
+    pub fn get_by_rank(&self, rank: TaskRank) -> Vec<&TaskData> {
+        if let Some(set) = self.rank_index.get(&rank) {
+            set.iter().map(move |&i| &self.store[i]).collect()
+        } else {
+            vec![]
+        }
+    }
+
+    fn set_done(&mut self, id: Uuid, completed_at: Option<f64>, timestamp: f64) -> bool {
+        if let Some(&i) = self.id_index.get(&id) {
+            if self.store[i].done == completed_at.is_some() {
+                return false;
+            }
+
+            let stamp = self.tick(timestamp);
+            let task = &mut self.store[i];
+            task.done = completed_at.is_some();
+            task.completed_at = completed_at;
+            task.stamps.done = stamp;
+            self.hash = Uuid::new_v4();
+            return true;
+        }
+
+        false
+    }
+
+    /// Issues the stamp of a write happening at `timestamp`.
+    fn tick(&mut self, timestamp: f64) -> Stamp {
+        self.clock = Stamp::next(self.clock, timestamp, self.replica);
+        self.clock
+    }
+
+    /// Position of a task inserted at `index` of `rank`, or at its end.
+    fn position_at(&self, rank: TaskRank, index: Option<usize>) -> String {
+        let tasks = self.get_by_rank(rank);
+        let index = index.unwrap_or(tasks.len()).min(tasks.len());
+        let before = index.checked_sub(1).map(|i| tasks[i].position.as_str());
+        let after = tasks.get(index).map(|task| task.position.as_str());
+        position::between(before, after)
+    }
+
+    /// Adds `task` in the order of its position, ties broken by id. A task
+    /// without a position is added at the end of its rank.
+    fn add(&mut self, mut task: TaskData) {
+        if task.position.is_empty() {
+            task.position = self.position_at(task.rank, None);
+        }
+
+        let i = self.store.insert(task);
+        let task = &self.store[i];
+        self.id_index.insert(task.id(), i);
+
+        let store = &self.store;
+        let key = (task.position.as_str(), task.id());
+        let v = self.rank_index.entry(task.rank).or_default();
+        let at = v
+            .binary_search_by(|&j| (store[j].position.as_str(), store[j].id()).cmp(&key))
+            .unwrap_or_else(|at| at);
+        v.insert(at, i);
+
+        self.hash = Uuid::new_v4();
+    }
+
+    fn remove_by_id(&mut self, id: Uuid) -> Option<TaskData> {
+        let i = self.id_index.remove(&id)?;
+        let task = self.store.remove(i);
+        if let Some(set) = self.rank_index.get_mut(&task.rank) {
+            if let Some(i) = set.index_of(&i) {
+                set.remove(i);
+            }
+        }
+
+        self.hash = Uuid::new_v4();
+        Some(task)
+    }
+}
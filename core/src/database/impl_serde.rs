@@ -0,0 +1,138 @@
+use super::migration::{self, SCHEMA_VERSION};
+use crate::*;
+use serde::{
+    de::{Error, IgnoredAny, Visitor},
+    ser::SerializeStruct,
+    Deserialize, Serialize,
+};
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+impl Serialize for DataBase {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut tasks = Vec::<&TaskData>::new();
+        for rank in [TaskRank::Primary, TaskRank::Secondary, TaskRank::Other] {
+            for task in self.get_by_rank(rank) {
+                tasks.push(task);
+            }
+        }
+
+        let tombstones = self.tombstones.iter().collect::<BTreeMap<_, _>>();
+
+        let mut s = serializer.serialize_struct("DataBase", 5)?;
+        s.serialize_field("version", &SCHEMA_VERSION)?;
+        s.serialize_field("replica", &self.replica)?;
+        s.serialize_field("clock", &self.clock)?;
+        s.serialize_field("tombstones", &tombstones)?;
+        s.serialize_field("tasks", &tasks)?;
+        s.end()
+    }
+}
+
+enum DataBaseField {
+    Version,
+    Replica,
+    Clock,
+    Tombstones,
+    Tasks,
+    Unknown,
+}
+
+struct DataBaseFieldVisitor;
+impl<'de> Visitor<'de> for DataBaseFieldVisitor {
+    type Value = DataBaseField;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("`version`, `replica`, `clock`, `tombstones` or `tasks`")
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        match v {
+            "version" => Ok(DataBaseField::Version),
+            "replica" => Ok(DataBaseField::Replica),
+            "clock" => Ok(DataBaseField::Clock),
+            "tombstones" => Ok(DataBaseField::Tombstones),
+            "tasks" => Ok(DataBaseField::Tasks),
+            _ => Ok(DataBaseField::Unknown),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for DataBaseField {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_identifier(DataBaseFieldVisitor)
+    }
+}
+
+struct DataBaseVisitor;
+impl<'de> Visitor<'de> for DataBaseVisitor {
+    type Value = DataBase;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("struct DataBase")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: serde::de::MapAccess<'de>,
+    {
+        let mut db = DataBase::default();
+
+        while let Some(key) = map.next_key::<DataBaseField>()? {
+            match key {
+                DataBaseField::Version => {
+                    map.next_value::<u64>()?;
+                }
+                DataBaseField::Replica => {
+                    db.replica = map.next_value::<Uuid>()?;
+                }
+                DataBaseField::Clock => {
+                    db.clock = map.next_value::<Stamp>()?;
+                }
+                DataBaseField::Tombstones => {
+                    let tombstones = map.next_value::<BTreeMap<Uuid, Stamp>>()?;
+                    db.tombstones = tombstones.into_iter().collect();
+                }
+                DataBaseField::Unknown => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+                DataBaseField::Tasks => {
+                    let tasks = map.next_value::<Vec<TaskData>>()?;
+                    for task in tasks {
+                        db.add(task);
+                    }
+                }
+            }
+        }
+
+        Ok(db)
+    }
+}
+
+/// Deserializes any known schema version by migrating it to `SCHEMA_VERSION`
+/// before reading the fields.
+impl<'de> Deserialize<'de> for DataBase {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let value = serde_json::Value::deserialize(deserializer)?;
+        let value = migration::migrate(value).map_err(D::Error::custom)?;
+        serde::Deserializer::deserialize_struct(
+            value,
+            "DataBase",
+            &["version", "replica", "clock", "tombstones", "tasks"],
+            DataBaseVisitor,
+        )
+        .map_err(D::Error::custom)
+    }
+}
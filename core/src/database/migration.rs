@@ -0,0 +1,119 @@
+use crate::{position, Stamp};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Version written by `impl Serialize for DataBase`.
+pub const SCHEMA_VERSION: u64 = 4;
+
+type Migration = fn(Value) -> Result<Value, String>;
+
+/// `MIGRATIONS[n]` upgrades a serialized `DataBase` from version `n` to `n + 1`.
+const MIGRATIONS: [Migration; SCHEMA_VERSION as usize] = [v0_to_v1, v1_to_v2, v2_to_v3, v3_to_v4];
+
+/// Upgrades a serialized `DataBase` of any known version to `SCHEMA_VERSION`.
+pub(super) fn migrate(mut value: Value) -> Result<Value, String> {
+    let mut version = version_of(&value)?;
+    if version > SCHEMA_VERSION {
+        return Err(format!(
+            "schema version {} is newer than supported version {}",
+            version, SCHEMA_VERSION
+        ));
+    }
+
+    while version < SCHEMA_VERSION {
+        value = MIGRATIONS[version as usize](value)?;
+        version += 1;
+    }
+
+    Ok(value)
+}
+
+fn version_of(value: &Value) -> Result<u64, String> {
+    let map = value.as_object().ok_or("expected struct DataBase")?;
+    match map.get("version") {
+        None => Ok(0),
+        Some(version) => version
+            .as_u64()
+            .ok_or_else(|| format!("invalid schema version: {}", version)),
+    }
+}
+
+/// v0 is the unversioned `{ "tasks": [...] }` layout.
+fn v0_to_v1(value: Value) -> Result<Value, String> {
+    let tasks = value.get("tasks").cloned().unwrap_or_else(|| json!([]));
+    Ok(json!({
+        "version": 1,
+        "tasks": tasks,
+    }))
+}
+
+/// v2 adds the completion state to every task.
+fn v1_to_v2(mut value: Value) -> Result<Value, String> {
+    for task in tasks_mut(&mut value)? {
+        let task = task.as_object_mut().ok_or("expected struct TaskData")?;
+        task.insert("done".to_owned(), json!(false));
+        task.insert("completed_at".to_owned(), Value::Null);
+    }
+    value["version"] = json!(2);
+    Ok(value)
+}
+
+/// v3 adds the created, edited and moved timestamps, unknown for existing tasks.
+fn v2_to_v3(mut value: Value) -> Result<Value, String> {
+    for task in tasks_mut(&mut value)? {
+        let task = task.as_object_mut().ok_or("expected struct TaskData")?;
+        task.insert("created_at".to_owned(), Value::Null);
+        task.insert("edited_at".to_owned(), Value::Null);
+        task.insert("moved_at".to_owned(), Value::Null);
+    }
+    value["version"] = json!(3);
+    Ok(value)
+}
+
+/// v4 makes the board mergeable: the copy gets its own replica, tasks get
+/// fractional positions in their current order and every register starts at
+/// the zero stamp.
+fn v3_to_v4(mut value: Value) -> Result<Value, String> {
+    let mut ranks = Vec::<String>::new();
+    for task in tasks_mut(&mut value)? {
+        let rank = task.get("rank").and_then(Value::as_str).unwrap_or_default();
+        ranks.push(rank.to_owned());
+    }
+
+    let mut positions = HashMap::<&str, std::vec::IntoIter<String>>::new();
+    for rank in &ranks {
+        let n = ranks.iter().filter(|r| *r == rank).count();
+        positions
+            .entry(rank)
+            .or_insert_with(|| position::sequence(n).into_iter());
+    }
+
+    for (task, rank) in tasks_mut(&mut value)?.iter_mut().zip(&ranks) {
+        let task = task.as_object_mut().ok_or("expected struct TaskData")?;
+        let position = positions.get_mut(rank.as_str()).and_then(Iterator::next);
+        task.insert("position".to_owned(), json!(position));
+        task.insert(
+            "stamps".to_owned(),
+            json!({
+                "value": Stamp::default(),
+                "rank": Stamp::default(),
+                "done": Stamp::default(),
+            }),
+        );
+    }
+
+    let map = value.as_object_mut().ok_or("expected struct DataBase")?;
+    map.insert("replica".to_owned(), json!(Uuid::new_v4()));
+    map.insert("clock".to_owned(), json!(Stamp::default()));
+    map.insert("tombstones".to_owned(), json!({}));
+    map.insert("version".to_owned(), json!(4));
+    Ok(value)
+}
+
+fn tasks_mut(value: &mut Value) -> Result<&mut Vec<Value>, String> {
+    value
+        .get_mut("tasks")
+        .and_then(Value::as_array_mut)
+        .ok_or_else(|| "expected field `tasks`".to_owned())
+}
@@ -0,0 +1,64 @@
+use crate::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub enum TaskEvent {
+    Add(TaskAddData),
+    Edit(TaskEditData),
+    Move(TaskMoveData),
+    Delete(TaskDeleteData),
+    Complete(TaskCompleteData),
+    Uncomplete(TaskUncompleteData),
+    Burn(TaskBurnData),
+    Swap(TaskSwapData),
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct TaskAddData {
+    pub id: Uuid,
+    pub rank: TaskRank,
+    pub value: String,
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct TaskEditData {
+    pub id: Uuid,
+    pub value: String,
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct TaskMoveData {
+    pub id: Uuid,
+    pub rank: TaskRank,
+    pub index: Option<usize>,
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct TaskDeleteData {
+    pub id: Uuid,
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct TaskCompleteData {
+    pub id: Uuid,
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct TaskUncompleteData {
+    pub id: Uuid,
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct TaskBurnData {
+    pub rank: TaskRank,
+    /// Keeps the unfinished tasks in `rank` instead of burning them.
+    #[serde(default)]
+    pub carry_over: bool,
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct TaskSwapData {
+    pub rank1: TaskRank,
+    pub rank2: TaskRank,
+}
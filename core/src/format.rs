@@ -0,0 +1,63 @@
+//! Plain-text formats other tools read and write. Each has a `to_*` function
+//! writing a board and a `from_*` function reading one back as a new board,
+//! which is then merged into or replaces the current one. `split_list`
+//! turns pasted lists into tasks.
+
+mod csv;
+mod date;
+mod ical;
+mod list;
+mod markdown;
+mod todotxt;
+
+pub use csv::*;
+pub use ical::*;
+pub use list::*;
+pub use markdown::*;
+pub use todotxt::*;
+
+use crate::*;
+
+/// A board holding `tasks` in the given order, read from a file at
+/// `timestamp`.
+fn board_of(tasks: impl IntoIterator<Item = TaskData>, timestamp: f64) -> DataBase {
+    let mut db = DataBase::default();
+    for mut task in tasks {
+        if db.get_by_id(task.id()).is_some() {
+            task = task.with_new_id();
+        }
+        task.created_at = task.created_at.or(Some(timestamp));
+        if task.done {
+            task.completed_at = task.completed_at.or(Some(timestamp));
+        }
+        db.insert_task(task);
+    }
+    db
+}
+
+/// A board with `(rank, value, done)` tasks in order, for the tests of the
+/// formats. Tasks are added at 1970-01-02 and finished at 1970-01-03.
+#[cfg(test)]
+fn test_board(tasks: &[(TaskRank, &str, bool)]) -> DataBase {
+    const DAY_MS: f64 = 24.0 * 60.0 * 60.0 * 1000.0;
+    let mut db = DataBase::default();
+    for &(rank, value, done) in tasks {
+        let id = uuid::Uuid::new_v4();
+        let value = value.to_owned();
+        db.apply_event(&TaskEvent::Add(TaskAddData { id, rank, value }), DAY_MS);
+        if done {
+            db.apply_event(&TaskEvent::Complete(TaskCompleteData { id }), 2.0 * DAY_MS);
+        }
+    }
+    db
+}
+
+/// The `(rank, value, done)` of every task, rank by rank.
+#[cfg(test)]
+fn test_contents(db: &DataBase) -> Vec<(TaskRank, String, bool)> {
+    TaskRank::ALL
+        .into_iter()
+        .flat_map(|rank| db.get_by_rank(rank))
+        .map(|task| (task.rank, task.value.clone(), task.done))
+        .collect()
+}
@@ -0,0 +1,259 @@
+use super::board_of;
+use super::date::DateTime;
+use crate::*;
+use uuid::Uuid;
+
+const COLUMNS: [&str; 10] = [
+    "id",
+    "rank",
+    "position",
+    "value",
+    "done",
+    "created_at",
+    "edited_at",
+    "moved_at",
+    "completed_at",
+    "burned_at",
+];
+
+/// Writes the tasks of the board, followed by `burned`, the burned tasks with
+/// the time they were burned at, as CSV (RFC 4180) with a header row.
+/// Times are UTC in ISO 8601. The file starts with a byte order mark so that
+/// spreadsheets read it as UTF-8.
+pub fn to_csv<'a>(db: &DataBase, burned: impl IntoIterator<Item = (f64, &'a TaskData)>) -> String {
+    let mut out = String::from("\u{feff}");
+    push_row(&mut out, COLUMNS.iter().map(|column| column.to_string()));
+
+    let tasks = TaskRank::ALL
+        .into_iter()
+        .flat_map(|rank| db.get_by_rank(rank))
+        .map(|task| (None, task));
+    let burned = burned
+        .into_iter()
+        .map(|(burned_at, task)| (Some(burned_at), task));
+    for (burned_at, task) in tasks.chain(burned) {
+        let time = |ms: Option<f64>| {
+            ms.map(|ms| DateTime::from_millis(ms).format_iso())
+                .unwrap_or_default()
+        };
+        push_row(
+            &mut out,
+            [
+                task.id().to_string(),
+                task.rank.to_string(),
+                task.position().to_owned(),
+                task.value.clone(),
+                task.done.to_string(),
+                time(task.created_at),
+                time(task.edited_at),
+                time(task.moved_at),
+                time(task.completed_at),
+                time(burned_at),
+            ],
+        );
+    }
+    out
+}
+
+/// Reads tasks from CSV with a header row, for example one exported by
+/// `to_csv` and edited in a spreadsheet. Only the `value` column is
+/// required. Tasks go to Primary without a `rank` column, keep their `id`
+/// when it is a UUID and are ordered by their row, not by `position`. Burned
+/// tasks are skipped.
+pub fn from_csv(text: &str, timestamp: f64) -> Result<DataBase, String> {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    let mut rows = parse_rows(text)?.into_iter();
+    let header = rows.next().ok_or("the file is empty")?;
+    let column = |name: &str| {
+        header
+            .iter()
+            .position(|column| column.trim().eq_ignore_ascii_case(name))
+    };
+    let value_column = column("value").ok_or("there is no `value` column")?;
+    let [id, rank, done, created_at, edited_at, moved_at, completed_at, burned_at] = [
+        "id",
+        "rank",
+        "done",
+        "created_at",
+        "edited_at",
+        "moved_at",
+        "completed_at",
+        "burned_at",
+    ]
+    .map(column);
+
+    let mut tasks = vec![];
+    // the header is line 1
+    for (number, row) in rows.enumerate() {
+        let field = |column: Option<usize>| {
+            column
+                .and_then(|column| row.get(column))
+                .map(|field| field.trim())
+                .filter(|field| !field.is_empty())
+        };
+        let time = |column: Option<usize>| {
+            field(column)
+                .map(|field| {
+                    DateTime::parse_iso(field)
+                        .map(DateTime::to_millis)
+                        .ok_or_else(|| format!("row {}: invalid time `{}`", number + 2, field))
+                })
+                .transpose()
+        };
+
+        let value = match row.get(value_column) {
+            Some(value) if !value.trim().is_empty() => value,
+            // blank lines at the end of a spreadsheet
+            _ => continue,
+        };
+        if field(burned_at).is_some() {
+            continue;
+        }
+        let rank = match field(rank) {
+            Some(rank) => rank
+                .parse()
+                .map_err(|e| format!("row {}: {}", number + 2, e))?,
+            None => TaskRank::Primary,
+        };
+        let mut task = match field(id).and_then(|id| Uuid::parse_str(id).ok()) {
+            Some(id) => TaskData::with_id(id, rank, value),
+            None => TaskData::new(rank, value),
+        };
+        task.done = field(done).map(is_true).unwrap_or(false);
+        task.created_at = time(created_at)?;
+        task.edited_at = time(edited_at)?;
+        task.moved_at = time(moved_at)?;
+        task.completed_at = time(completed_at)?.filter(|_| task.done);
+        tasks.push(task);
+    }
+    Ok(board_of(tasks, timestamp))
+}
+
+fn is_true(field: &str) -> bool {
+    ["true", "yes", "x", "1"]
+        .iter()
+        .any(|t| field.eq_ignore_ascii_case(t))
+}
+
+fn push_row(out: &mut String, fields: impl IntoIterator<Item = String>) {
+    let fields = fields
+        .into_iter()
+        .map(|field| {
+            if field.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field
+            }
+        })
+        .collect::<Vec<_>>();
+    out.push_str(&fields.join(","));
+    out.push_str("\r\n");
+}
+
+/// Splits CSV into rows of fields. Quoted fields may hold commas, line
+/// breaks and doubled quotes.
+fn parse_rows(text: &str) -> Result<Vec<Vec<String>>, String> {
+    let mut rows = vec![];
+    let mut row = vec![];
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (quoted, c) {
+            (true, '"') if chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            (true, '"') => quoted = false,
+            (true, '\r') if chars.peek() == Some(&'\n') => {}
+            (true, c) => field.push(c),
+            (false, '"') if field.is_empty() => quoted = true,
+            (false, ',') => row.push(std::mem::take(&mut field)),
+            (false, '\r') if chars.peek() == Some(&'\n') => {}
+            (false, '\n') => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            (false, c) => field.push(c),
+        }
+    }
+    if quoted {
+        return Err("a quoted field is not closed".to_owned());
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{test_board, test_contents};
+    use super::*;
+
+    #[test]
+    fn tasks_round_trip() {
+        let db = test_board(&[
+            (TaskRank::Primary, "plain", false),
+            (TaskRank::Primary, "comma, \"quotes\"", true),
+            (TaskRank::Secondary, "two\nlines", false),
+            (TaskRank::Other, "  kept as is  ", true),
+        ]);
+
+        let read = from_csv(&to_csv(&db, []), 0.0).unwrap();
+        assert_eq!(test_contents(&read), test_contents(&db));
+        for task in db.tasks() {
+            let read = read.get_by_id(task.id()).expect("the id to be kept");
+            assert_eq!(read.created_at, task.created_at);
+            assert_eq!(read.edited_at, task.edited_at);
+            assert_eq!(read.moved_at, task.moved_at);
+            assert_eq!(read.completed_at, task.completed_at);
+        }
+    }
+
+    #[test]
+    fn burned_tasks_are_written_but_not_read() {
+        let db = test_board(&[(TaskRank::Primary, "on the board", false)]);
+        let burned = TaskData::new(TaskRank::Secondary, "burned");
+
+        let text = to_csv(&db, [(3000.0, &burned)]);
+        let rows = parse_rows(text.strip_prefix('\u{feff}').unwrap()).unwrap();
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0], COLUMNS);
+        assert_eq!(rows[2][3], "burned");
+        assert_eq!(rows[2][9], "1970-01-01T00:00:03Z");
+
+        let read = from_csv(&text, 0.0).unwrap();
+        assert_eq!(
+            test_contents(&read),
+            [(TaskRank::Primary, "on the board".to_owned(), false)]
+        );
+    }
+
+    #[test]
+    fn spreadsheet_files_are_read() {
+        let text = "Value,Done,Rank\r\n\
+                    \"multi\r\nline\",yes,Secondary\r\n\
+                    only a value,,\r\n\
+                    ,,\r\n";
+        let db = from_csv(text, 0.0).unwrap();
+
+        assert_eq!(
+            test_contents(&db),
+            [
+                (TaskRank::Primary, "only a value".to_owned(), false),
+                (TaskRank::Secondary, "multi\nline".to_owned(), true),
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_files_are_errors() {
+        assert!(from_csv("id,rank\r\n1,Primary\r\n", 0.0).is_err());
+        assert!(from_csv("value\r\n\"open", 0.0).is_err());
+        assert!(from_csv("value,rank\r\ntask,Someday\r\n", 0.0).is_err());
+        assert!(from_csv("", 0.0).is_err());
+    }
+}
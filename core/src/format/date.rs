@@ -0,0 +1,179 @@
+//! UTC calendar dates of the millisecond timestamps in `TaskData`, without a
+//! time zone database.
+
+const MS_PER_DAY: i64 = 86_400_000;
+
+/// A point in time in UTC, to the second.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) struct DateTime {
+    pub year: i64,
+    pub month: u32,
+    pub day: u32,
+    pub hour: u32,
+    pub minute: u32,
+    pub second: u32,
+}
+
+impl DateTime {
+    pub fn from_millis(ms: f64) -> Self {
+        let ms = ms as i64;
+        let (year, month, day) = civil_from_days(ms.div_euclid(MS_PER_DAY));
+        let seconds = ms.rem_euclid(MS_PER_DAY) / 1000;
+        DateTime {
+            year,
+            month,
+            day,
+            hour: (seconds / 3600) as u32,
+            minute: (seconds / 60 % 60) as u32,
+            second: (seconds % 60) as u32,
+        }
+    }
+
+    pub fn to_millis(self) -> f64 {
+        let days = days_from_civil(self.year, self.month, self.day);
+        let seconds =
+            days * 86_400 + self.hour as i64 * 3600 + self.minute as i64 * 60 + self.second as i64;
+        (seconds * 1000) as f64
+    }
+
+    pub fn date(year: i64, month: u32, day: u32) -> Option<Self> {
+        let valid = (1..=12).contains(&month) && day >= 1 && day <= days_in_month(year, month);
+        valid.then_some(DateTime {
+            year,
+            month,
+            day,
+            hour: 0,
+            minute: 0,
+            second: 0,
+        })
+    }
+
+    /// `YYYY-MM-DD`.
+    pub fn format_date(&self) -> String {
+        format!("{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+
+    /// Reads `YYYY-MM-DD`.
+    pub fn parse_date(s: &str) -> Option<Self> {
+        let mut parts = s.splitn(3, '-');
+        let year = number(parts.next()?, 4)?;
+        let month = number(parts.next()?, 2)?;
+        let day = number(parts.next()?, 2)?;
+        DateTime::date(year, month as u32, day as u32)
+    }
+
+    /// `YYYY-MM-DDTHH:MM:SSZ`, which spreadsheets read as a date.
+    pub fn format_iso(&self) -> String {
+        format!(
+            "{}T{:02}:{:02}:{:02}Z",
+            self.format_date(),
+            self.hour,
+            self.minute,
+            self.second
+        )
+    }
+
+    /// Reads `YYYY-MM-DD`, or `YYYY-MM-DDTHH:MM:SS` followed by an optional
+    /// fraction and `Z`. A space may separate the date and the time. Times
+    /// without the `Z` are taken as UTC.
+    pub fn parse_iso(s: &str) -> Option<Self> {
+        let s = s.trim();
+        let (date, time) = match s.find(['T', ' ']) {
+            Some(at) => (&s[..at], Some(&s[at + 1..])),
+            None => (s, None),
+        };
+        let mut datetime = DateTime::parse_date(date)?;
+        if let Some(time) = time {
+            let time = time.strip_suffix('Z').unwrap_or(time);
+            let time = time.split('.').next().unwrap_or_default();
+            let mut parts = time.split(':');
+            datetime.hour = number(parts.next()?, 2)? as u32;
+            datetime.minute = number(parts.next()?, 2)? as u32;
+            datetime.second = match parts.next() {
+                Some(second) => number(second, 2)? as u32,
+                None => 0,
+            };
+            if datetime.hour > 23 || datetime.minute > 59 || datetime.second > 60 {
+                return None;
+            }
+        }
+        Some(datetime)
+    }
+
+    /// `YYYYMMDDTHHMMSSZ`, the iCalendar form.
+    pub fn format_basic(&self) -> String {
+        format!(
+            "{:04}{:02}{:02}T{:02}{:02}{:02}Z",
+            self.year, self.month, self.day, self.hour, self.minute, self.second
+        )
+    }
+
+    /// Reads `YYYYMMDD`, `YYYYMMDDTHHMMSS` or `YYYYMMDDTHHMMSSZ`. Times
+    /// without the `Z` are taken as UTC.
+    pub fn parse_basic(s: &str) -> Option<Self> {
+        let (date, time) = s.split_once('T').unwrap_or((s, ""));
+        if date.len() != 8 {
+            return None;
+        }
+        let mut datetime = DateTime::date(
+            number(&date[..4], 4)?,
+            number(&date[4..6], 2)? as u32,
+            number(&date[6..], 2)? as u32,
+        )?;
+        if s.contains('T') {
+            let time = time.strip_suffix('Z').unwrap_or(time);
+            if time.len() != 6 {
+                return None;
+            }
+            datetime.hour = number(&time[..2], 2)? as u32;
+            datetime.minute = number(&time[2..4], 2)? as u32;
+            datetime.second = number(&time[4..], 2)? as u32;
+            if datetime.hour > 23 || datetime.minute > 59 || datetime.second > 60 {
+                return None;
+            }
+        }
+        Some(datetime)
+    }
+}
+
+/// Parses exactly `digits` ASCII digits.
+pub(super) fn number(s: &str, digits: usize) -> Option<i64> {
+    if s.len() != digits || !s.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    s.parse().ok()
+}
+
+fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+// http://howardhinnant.github.io/date_algorithms.html
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let month = month as i64;
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
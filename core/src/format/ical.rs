@@ -0,0 +1,356 @@
+use super::board_of;
+use super::date::DateTime;
+use crate::*;
+use uuid::Uuid;
+
+/// Lines longer than this many octets are folded.
+const LINE_LIMIT: usize = 75;
+
+/// Writes the board as an iCalendar (RFC 5545) file with one VTODO per task.
+/// The first line of the value is the SUMMARY and the others the
+/// DESCRIPTION. The rank is the PRIORITY: 1 for Primary, 5 for Secondary and
+/// 9 for Other.
+pub fn to_ical(db: &DataBase) -> String {
+    let mut out = String::new();
+    push_line(&mut out, "BEGIN:VCALENDAR");
+    push_line(&mut out, "VERSION:2.0");
+    push_line(&mut out, "PRODID:-//Burner List//Burner List//EN");
+    for rank in TaskRank::ALL {
+        for task in db.get_by_rank(rank) {
+            push_todo(&mut out, task);
+        }
+    }
+    push_line(&mut out, "END:VCALENDAR");
+    out
+}
+
+fn push_todo(out: &mut String, task: &TaskData) {
+    let time = |ms: Option<f64>| DateTime::from_millis(ms.unwrap_or(0.0)).format_basic();
+    let (summary, description) = task
+        .value
+        .split_once('\n')
+        .unwrap_or((task.value.as_str(), ""));
+
+    push_line(out, "BEGIN:VTODO");
+    push_line(out, &format!("UID:{}", task.id()));
+    push_line(
+        out,
+        &format!("DTSTAMP:{}", time(task.edited_at.or(task.created_at))),
+    );
+    if task.created_at.is_some() {
+        push_line(out, &format!("CREATED:{}", time(task.created_at)));
+    }
+    if task.edited_at.is_some() {
+        push_line(out, &format!("LAST-MODIFIED:{}", time(task.edited_at)));
+    }
+    push_line(out, &format!("SUMMARY:{}", escape(summary)));
+    if !description.is_empty() {
+        push_line(out, &format!("DESCRIPTION:{}", escape(description)));
+    }
+    push_line(out, &format!("PRIORITY:{}", priority_of(task.rank)));
+    if task.done {
+        push_line(out, "STATUS:COMPLETED");
+        if task.completed_at.is_some() {
+            push_line(out, &format!("COMPLETED:{}", time(task.completed_at)));
+        }
+    } else {
+        push_line(out, "STATUS:NEEDS-ACTION");
+    }
+    push_line(out, "END:VTODO");
+}
+
+/// Reads the VTODOs of an iCalendar file, in order. Other components, and
+/// the ones nested in a VTODO, are ignored. Tasks keep their UID as id when
+/// it is a UUID.
+pub fn from_ical(text: &str, timestamp: f64) -> Result<DataBase, String> {
+    let lines = unfold(text);
+    if !lines
+        .iter()
+        .any(|line| line.eq_ignore_ascii_case("BEGIN:VCALENDAR"))
+    {
+        return Err("not an iCalendar file".to_owned());
+    }
+
+    let mut tasks = vec![];
+    let mut todo: Option<Todo> = None;
+    // depth of the components inside the VTODO, such as a VALARM, whose
+    // properties are not the task's
+    let mut nested = 0;
+    for line in &lines {
+        let (name, value) = match split_line(line) {
+            Some(pair) => pair,
+            None => continue,
+        };
+        match (name.as_str(), todo.as_mut()) {
+            ("BEGIN", None) if value.eq_ignore_ascii_case("VTODO") => todo = Some(Todo::default()),
+            ("BEGIN", Some(_)) => nested += 1,
+            ("END", Some(_)) if nested > 0 => nested -= 1,
+            ("END", Some(_)) if value.eq_ignore_ascii_case("VTODO") => {
+                tasks.extend(todo.take().map(Todo::into_task))
+            }
+            (_, Some(todo)) if nested == 0 => todo.set(&name, value),
+            _ => {}
+        }
+    }
+    Ok(board_of(tasks, timestamp))
+}
+
+/// The properties of a VTODO that make up a task.
+#[derive(Default)]
+struct Todo {
+    uid: Option<Uuid>,
+    summary: String,
+    description: String,
+    priority: u32,
+    done: bool,
+    created_at: Option<f64>,
+    edited_at: Option<f64>,
+    completed_at: Option<f64>,
+}
+
+impl Todo {
+    fn set(&mut self, name: &str, value: &str) {
+        let time = || DateTime::parse_basic(value).map(DateTime::to_millis);
+        match name {
+            "UID" => self.uid = Uuid::parse_str(value).ok(),
+            "SUMMARY" => self.summary = unescape(value),
+            "DESCRIPTION" => self.description = unescape(value),
+            "PRIORITY" => self.priority = value.trim().parse().unwrap_or(0),
+            "STATUS" => self.done = value.eq_ignore_ascii_case("COMPLETED"),
+            "CREATED" => self.created_at = time(),
+            "LAST-MODIFIED" => self.edited_at = time(),
+            "COMPLETED" => {
+                self.done = true;
+                self.completed_at = time();
+            }
+            _ => {}
+        }
+    }
+
+    fn into_task(self) -> TaskData {
+        let value = if self.description.is_empty() {
+            self.summary
+        } else {
+            format!("{}\n{}", self.summary, self.description)
+        };
+        let rank = rank_of(self.priority);
+        let mut task = match self.uid {
+            Some(id) => TaskData::with_id(id, rank, &value),
+            None => TaskData::new(rank, &value),
+        };
+        task.done = self.done;
+        task.created_at = self.created_at;
+        task.edited_at = self.edited_at;
+        task.completed_at = self.completed_at.filter(|_| self.done);
+        task
+    }
+}
+
+/// Appends a content line, folded so that no line is longer than
+/// `LINE_LIMIT` octets, without splitting a character.
+fn push_line(out: &mut String, line: &str) {
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > LINE_LIMIT {
+            out.push_str("\r\n ");
+            // the leading space counts towards the limit
+            width = 1;
+        }
+        out.push(c);
+        width += c.len_utf8();
+    }
+    out.push_str("\r\n");
+}
+
+/// Joins folded lines back together.
+fn unfold(text: &str) -> Vec<String> {
+    let mut lines: Vec<String> = vec![];
+    for line in text.split('\n') {
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some(last)) => last.push_str(rest),
+            _ if line.is_empty() => {}
+            _ => lines.push(line.to_owned()),
+        }
+    }
+    lines
+}
+
+/// Splits a content line into its upper-cased name, without parameters, and
+/// its value.
+fn split_line(line: &str) -> Option<(String, &str)> {
+    // the value starts at the first colon outside a quoted parameter
+    let mut quoted = false;
+    let colon = line.char_indices().find_map(|(i, c)| match c {
+        '"' => {
+            quoted = !quoted;
+            None
+        }
+        ':' if !quoted => Some(i),
+        _ => None,
+    })?;
+    let name = line[..colon].split(';').next().unwrap_or_default();
+    Some((name.to_ascii_uppercase(), &line[colon + 1..]))
+}
+
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            ';' => out.push_str("\\;"),
+            ',' => out.push_str("\\,"),
+            '\n' => out.push_str("\\n"),
+            '\r' => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+fn unescape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') | Some('N') => out.push('\n'),
+            Some(c) => out.push(c),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+fn priority_of(rank: TaskRank) -> u32 {
+    match rank {
+        TaskRank::Primary => 1,
+        TaskRank::Secondary => 5,
+        TaskRank::Other => 9,
+    }
+}
+
+/// 1 to 4 are high priorities, 5 is medium and 6 to 9 are low. 0 is
+/// undefined.
+fn rank_of(priority: u32) -> TaskRank {
+    match priority {
+        1..=4 => TaskRank::Primary,
+        5 => TaskRank::Secondary,
+        _ => TaskRank::Other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const UID: &str = "5f0c2a7e-3b1d-4e8f-9a6c-2d4b6f8a0c13";
+
+    fn calendar(todos: &str) -> String {
+        format!(
+            "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//Test//EN\r\n{}END:VCALENDAR\r\n",
+            todos
+        )
+    }
+
+    #[test]
+    fn nested_components_do_not_leak_into_the_task() {
+        let text = calendar(
+            "BEGIN:VTODO\r\n\
+             UID:5f0c2a7e-3b1d-4e8f-9a6c-2d4b6f8a0c13\r\n\
+             SUMMARY:Submit the report\r\n\
+             BEGIN:VALARM\r\n\
+             ACTION:DISPLAY\r\n\
+             DESCRIPTION:Reminder\r\n\
+             TRIGGER:-PT15M\r\n\
+             END:VALARM\r\n\
+             PRIORITY:1\r\n\
+             STATUS:NEEDS-ACTION\r\n\
+             END:VTODO\r\n\
+             BEGIN:VEVENT\r\n\
+             SUMMARY:Not a task\r\n\
+             END:VEVENT\r\n",
+        );
+        let db = from_ical(&text, 0.0).unwrap();
+
+        assert_eq!(db.tasks().count(), 1);
+        let task = &db.get_by_rank(TaskRank::Primary)[0];
+        assert_eq!(task.id().to_string(), UID);
+        assert_eq!(task.value, "Submit the report");
+        assert!(!task.done);
+    }
+
+    #[test]
+    fn folded_lines_are_joined() {
+        let text = calendar(
+            "BEGIN:VTODO\r\n\
+             SUMMARY:A summary that was folded \r\n \
+             by the writer\r\n\
+             DESCRIPTION:燃やす\r\n\tタスク\r\n\
+             PRIORITY:5\r\n\
+             END:VTODO\r\n",
+        );
+        let db = from_ical(&text, 0.0).unwrap();
+
+        let task = &db.get_by_rank(TaskRank::Secondary)[0];
+        assert_eq!(
+            task.value,
+            "A summary that was folded by the writer\n燃やすタスク"
+        );
+    }
+
+    #[test]
+    fn escapes_are_read() {
+        let text = calendar(
+            "BEGIN:VTODO\r\n\
+             SUMMARY:milk\\, eggs\\; bread\r\n\
+             DESCRIPTION:first\\nsecond\\Nthird \\\\ done\r\n\
+             END:VTODO\r\n",
+        );
+        let db = from_ical(&text, 0.0).unwrap();
+
+        let task = &db.get_by_rank(TaskRank::Other)[0];
+        assert_eq!(
+            task.value,
+            "milk, eggs; bread\nfirst\nsecond\nthird \\ done"
+        );
+    }
+
+    #[test]
+    fn tasks_round_trip() {
+        let mut db = DataBase::default();
+        let values = [
+            (TaskRank::Primary, "short"),
+            (TaskRank::Primary, "a, b; c\\d\nsecond line"),
+            (
+                TaskRank::Secondary,
+                "とても長いタスクの名前は七十五オクテットを超えると折り返されて書き出されます",
+            ),
+            (TaskRank::Other, "done"),
+        ];
+        for (rank, value) in values {
+            let id = Uuid::new_v4();
+            let value = value.to_owned();
+            db.apply_event(&TaskEvent::Add(TaskAddData { id, rank, value }), 1000.0);
+        }
+        let done = db.get_by_rank(TaskRank::Other)[0].id();
+        db.apply_event(&TaskEvent::Complete(TaskCompleteData { id: done }), 2000.0);
+
+        let text = to_ical(&db);
+        assert!(text.split("\r\n").all(|line| line.len() <= LINE_LIMIT));
+        let read = from_ical(&text, 0.0).unwrap();
+        for rank in TaskRank::ALL {
+            let tasks = |db: &DataBase| {
+                db.get_by_rank(rank)
+                    .into_iter()
+                    .map(|task| (task.id(), task.value.clone(), task.done))
+                    .collect::<Vec<_>>()
+            };
+            assert_eq!(tasks(&read), tasks(&db));
+        }
+        assert_eq!(read.get_by_id(done).unwrap().completed_at, Some(2000.0));
+    }
+}
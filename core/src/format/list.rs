@@ -0,0 +1,169 @@
+use super::markdown::checkbox;
+
+/// A task taken from a pasted list.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ListItem {
+    pub value: String,
+    pub done: bool,
+}
+
+/// Splits pasted text into tasks. When some lines start with a bullet (`-`,
+/// `*`, `+`, `•`) or a number (`1.`, `1)`), each of them starts a task and
+/// the lines between them continue the previous one. Otherwise every line is
+/// a task. Bullets and `[ ]`/`[x]` checkboxes are removed; a checked box
+/// makes a finished task.
+pub fn split_list(text: &str) -> Vec<ListItem> {
+    let lines = text
+        .lines()
+        .map(str::trim_end)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>();
+    let bulleted = lines.iter().any(|line| strip_marker(line).is_some());
+
+    let mut items: Vec<ListItem> = vec![];
+    for line in lines {
+        let item = match strip_marker(line) {
+            Some(item) => item,
+            None if bulleted && !items.is_empty() => {
+                let last = items.last_mut().expect("items not to be empty");
+                last.value.push('\n');
+                last.value.push_str(line.trim_start());
+                continue;
+            }
+            None => line.trim_start(),
+        };
+        let (done, value) = checkbox(item);
+        items.push(ListItem {
+            value: value.trim().to_owned(),
+            done,
+        });
+    }
+    items.retain(|item| !item.value.is_empty());
+    items
+}
+
+/// The rest of a line that starts with a bullet or a number, after any
+/// indentation. The marker is followed by a space or a tab, or ends the line.
+fn strip_marker(line: &str) -> Option<&str> {
+    let line = line.trim_start();
+    if let Some(rest) = ['-', '*', '+', '•']
+        .into_iter()
+        .find_map(|bullet| line.strip_prefix(bullet))
+    {
+        return after_marker(rest);
+    }
+
+    let digits = line.len() - line.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    if digits == 0 {
+        return None;
+    }
+    line[digits..]
+        .strip_prefix(['.', ')'])
+        .and_then(after_marker)
+}
+
+fn after_marker(rest: &str) -> Option<&str> {
+    if rest.is_empty() {
+        Some(rest)
+    } else {
+        rest.strip_prefix([' ', '\t'])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn items(items: &[(&str, bool)]) -> Vec<ListItem> {
+        items
+            .iter()
+            .map(|&(value, done)| ListItem {
+                value: value.to_owned(),
+                done,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn bullets_and_numbers_are_markers() {
+        assert_eq!(strip_marker("- dash"), Some("dash"));
+        assert_eq!(strip_marker("* star"), Some("star"));
+        assert_eq!(strip_marker("+ plus"), Some("plus"));
+        assert_eq!(strip_marker("• dot"), Some("dot"));
+        assert_eq!(strip_marker("-\ttab"), Some("tab"));
+        assert_eq!(strip_marker("1. one"), Some("one"));
+        assert_eq!(strip_marker("12) twelve"), Some("twelve"));
+        assert_eq!(strip_marker("    - indented"), Some("indented"));
+        assert_eq!(strip_marker("\t3. indented"), Some("indented"));
+        assert_eq!(strip_marker("-"), Some(""));
+        assert_eq!(strip_marker("1."), Some(""));
+    }
+
+    #[test]
+    fn other_lines_are_not_markers() {
+        assert_eq!(strip_marker("plain"), None);
+        assert_eq!(strip_marker("-dash without a space"), None);
+        assert_eq!(strip_marker("1.5 kg of rice"), None);
+        assert_eq!(strip_marker("2024 was a year"), None);
+        assert_eq!(strip_marker("a. letter"), None);
+        assert_eq!(strip_marker(""), None);
+    }
+
+    #[test]
+    fn bulleted_lines_start_tasks() {
+        let text = "- first\n* second\n+ third\n1. fourth\n2) fifth";
+        assert_eq!(
+            split_list(text),
+            items(&[
+                ("first", false),
+                ("second", false),
+                ("third", false),
+                ("fourth", false),
+                ("fifth", false),
+            ])
+        );
+    }
+
+    #[test]
+    fn checkboxes_set_the_completion() {
+        let text = "- [ ] open\n- [x] done\n- [X] also done\n- [x]tight\n- [] not a box";
+        assert_eq!(
+            split_list(text),
+            items(&[
+                ("open", false),
+                ("done", true),
+                ("also done", true),
+                ("tight", true),
+                ("[] not a box", false),
+            ])
+        );
+    }
+
+    #[test]
+    fn other_lines_continue_the_previous_task() {
+        let text = "intro without a bullet\n- first\n  more of first\n\n    - nested\nafter";
+        assert_eq!(
+            split_list(text),
+            items(&[
+                ("intro without a bullet", false),
+                ("first\nmore of first", false),
+                ("nested\nafter", false),
+            ])
+        );
+    }
+
+    #[test]
+    fn without_markers_every_line_is_a_task() {
+        let text = "  one  \n\n\ttwo\n   \nthree\r\n";
+        assert_eq!(
+            split_list(text),
+            items(&[("one", false), ("two", false), ("three", false)])
+        );
+    }
+
+    #[test]
+    fn empty_items_are_dropped() {
+        assert_eq!(split_list("- \n- [ ]\n- kept\n"), items(&[("kept", false)]));
+        assert!(split_list("\n  \n").is_empty());
+    }
+}
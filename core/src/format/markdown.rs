@@ -0,0 +1,168 @@
+use super::board_of;
+use crate::*;
+
+/// Writes the board as one `# Rank` heading per rank, followed by its tasks
+/// as a task list. Lines after the first of a task are indented by two
+/// spaces.
+pub fn to_markdown(db: &DataBase) -> String {
+    let mut out = String::new();
+    for rank in TaskRank::ALL {
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        out.push_str(&format!("# {}\n\n", rank));
+        for task in db.get_by_rank(rank) {
+            let check = if task.done { 'x' } else { ' ' };
+            let mut lines = task.value.lines();
+            out.push_str(&format!(
+                "- [{}] {}\n",
+                check,
+                lines.next().unwrap_or_default()
+            ));
+            for line in lines {
+                if line.is_empty() {
+                    out.push('\n');
+                } else {
+                    out.push_str(&format!("  {}\n", line));
+                }
+            }
+        }
+    }
+    out
+}
+
+/// Reads a board written by `to_markdown`, or edited by hand: `-`, `*` and
+/// `+` bullets are accepted, the checkbox is optional and tasks before the
+/// first heading go to Primary. Every heading must name a rank; other text
+/// between the tasks is ignored.
+pub fn from_markdown(text: &str, timestamp: f64) -> Result<DataBase, String> {
+    let mut tasks: Vec<TaskData> = vec![];
+    let mut rank = TaskRank::Primary;
+    // blank lines are only part of a task if an indented line follows
+    let mut blank_lines = 0;
+    // whether the previous line belongs to a task
+    let mut in_task = false;
+
+    for (number, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            blank_lines += 1;
+            continue;
+        }
+
+        if let Some(heading) = line.strip_prefix('#') {
+            let heading = heading.trim_start_matches('#').trim();
+            rank = heading
+                .parse()
+                .map_err(|e| format!("line {}: {}", number + 1, e))?;
+            in_task = false;
+        } else if let Some(item) = list_item(line) {
+            let (done, value) = checkbox(item);
+            let mut task = TaskData::new(rank, value);
+            task.done = done;
+            tasks.push(task);
+            in_task = true;
+        } else if let (true, Some(task), Some(rest)) = (in_task, tasks.last_mut(), indented(line)) {
+            for _ in 0..=blank_lines {
+                task.value.push('\n');
+            }
+            task.value.push_str(rest);
+        } else {
+            in_task = false;
+        }
+        blank_lines = 0;
+    }
+
+    Ok(board_of(tasks, timestamp))
+}
+
+fn list_item(line: &str) -> Option<&str> {
+    ["- ", "* ", "+ "]
+        .into_iter()
+        .find_map(|bullet| line.strip_prefix(bullet))
+}
+
+/// Removes a leading `[ ]` or `[x]` and tells whether it was checked.
+pub(super) fn checkbox(item: &str) -> (bool, &str) {
+    if let Some(value) = item
+        .strip_prefix("[ ] ")
+        .or_else(|| item.strip_prefix("[ ]"))
+    {
+        (false, value)
+    } else if let Some(value) = ["[x] ", "[X] ", "[x]", "[X]"]
+        .into_iter()
+        .find_map(|check| item.strip_prefix(check))
+    {
+        (true, value)
+    } else {
+        (false, item)
+    }
+}
+
+/// The rest of a continuation line, indented by two spaces or a tab.
+fn indented(line: &str) -> Option<&str> {
+    line.strip_prefix("  ").or_else(|| line.strip_prefix('\t'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{test_board, test_contents};
+    use super::*;
+
+    #[test]
+    fn tasks_round_trip() {
+        let db = test_board(&[
+            (TaskRank::Primary, "first", false),
+            (TaskRank::Primary, "two\nlines", true),
+            (TaskRank::Secondary, "a blank\n\nline inside", false),
+            (TaskRank::Other, "# not a heading, - not a bullet", false),
+            (TaskRank::Other, "`code` and **bold**", true),
+        ]);
+
+        let read = from_markdown(&to_markdown(&db), 0.0).unwrap();
+        assert_eq!(test_contents(&read), test_contents(&db));
+    }
+
+    #[test]
+    fn boards_are_written_as_task_lists() {
+        let db = test_board(&[
+            (TaskRank::Primary, "first", true),
+            (TaskRank::Other, "two\nlines", false),
+        ]);
+
+        assert_eq!(
+            to_markdown(&db),
+            "# Primary\n\n- [x] first\n\n# Secondary\n\n\n# Other\n\n- [ ] two\n  lines\n"
+        );
+    }
+
+    #[test]
+    fn hand_written_lists_are_read() {
+        let text = "- before any heading\n\
+                    \n\
+                    ## Secondary\n\
+                    Some notes that are not a task.\n\
+                    * [X] starred\n\
+                    + plain\n\
+                    \tcontinued by a tab\n";
+        let db = from_markdown(text, 0.0).unwrap();
+
+        assert_eq!(
+            test_contents(&db),
+            [
+                (TaskRank::Primary, "before any heading".to_owned(), false),
+                (TaskRank::Secondary, "starred".to_owned(), true),
+                (
+                    TaskRank::Secondary,
+                    "plain\ncontinued by a tab".to_owned(),
+                    false
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn unknown_headings_are_errors() {
+        let error = from_markdown("# Primary\n- task\n# Someday\n", 0.0).unwrap_err();
+        assert!(error.starts_with("line 3:"), "{}", error);
+    }
+}
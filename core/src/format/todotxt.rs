@@ -0,0 +1,318 @@
+use super::board_of;
+use super::date::DateTime;
+use crate::*;
+
+/// Writes the board as a todo.txt file, one task per line. The rank is the
+/// priority, `(A)` for Primary, `(B)` for Secondary and `(C)` for Other, and
+/// is kept as a `pri:` tag on finished tasks, which have no priority.
+/// `+project`, `@context` and `key:value` tags are part of the value, so they
+/// are written as they are. Line breaks in a value are written as `\n`,
+/// backslashes as `\\`, and a backslash goes before a first word that would
+/// be read as a priority, a date or the `x` of a finished task. A finished
+/// task with a creation date but no completion date is written as completed
+/// on the day it was created, since a single date is read as the completion.
+pub fn to_todotxt(db: &DataBase) -> String {
+    let mut out = String::new();
+    for rank in TaskRank::ALL {
+        let priority = priority_of(rank);
+        for task in db.get_by_rank(rank) {
+            let mut line = vec![];
+            if task.done {
+                line.push("x".to_owned());
+                if let Some(completed_at) = task.completed_at.or(task.created_at) {
+                    line.push(DateTime::from_millis(completed_at).format_date());
+                }
+            } else {
+                line.push(format!("({})", priority));
+            }
+            if let Some(created_at) = task.created_at {
+                line.push(DateTime::from_millis(created_at).format_date());
+            }
+            line.push(escape(&task.value));
+            if task.done {
+                line.push(format!("pri:{}", priority));
+            }
+            out.push_str(&line.join(" "));
+            out.push('\n');
+        }
+    }
+    out
+}
+
+/// Reads a todo.txt file, keeping the order of the lines within each rank.
+/// Tasks without a priority, or with one below `(C)`, go to Other. Fields are
+/// separated by a single space, so that the spaces around a value are kept.
+/// Only a `pri:` tag at the end of a finished task is read as its rank.
+pub fn from_todotxt(text: &str, timestamp: f64) -> Result<DataBase, String> {
+    let tasks = text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(parse_line)
+        .collect::<Vec<_>>();
+    Ok(board_of(tasks, timestamp))
+}
+
+fn parse_line(line: &str) -> TaskData {
+    let mut rest = line;
+    let mut done = false;
+    let mut priority = None;
+    let mut completed_at = None;
+    let mut created_at = None;
+
+    if let Some(after) = rest.strip_prefix("x ") {
+        done = true;
+        rest = after;
+        // some tools keep the priority of finished tasks
+        if let Some((p, after)) = take_priority(rest) {
+            priority = Some(p);
+            rest = after;
+        }
+        if let Some((date, after)) = take_date(rest) {
+            completed_at = Some(date);
+            rest = after;
+        }
+    } else if let Some((p, after)) = take_priority(rest) {
+        priority = Some(p);
+        rest = after;
+    }
+    if let Some((date, after)) = take_date(rest) {
+        created_at = Some(date);
+        rest = after;
+    }
+
+    if done {
+        if let Some((before, tag)) = rest.rsplit_once(' ') {
+            if let Some(p) = tag.strip_prefix("pri:").and_then(single_letter) {
+                priority = priority.or(Some(p));
+                rest = before;
+            }
+        }
+    }
+    if let Some(after) = rest.strip_prefix('\\') {
+        if is_reserved(first_word(after)) {
+            rest = after;
+        }
+    }
+    let value = unescape(rest);
+
+    let mut task = TaskData::new(rank_of(priority), &value);
+    task.done = done;
+    task.completed_at = completed_at;
+    task.created_at = created_at;
+    task
+}
+
+/// Puts `value` on a single line, after the fields that come before it.
+fn escape(value: &str) -> String {
+    let escaped = value.replace('\\', "\\\\").replace('\n', "\\n");
+    if is_reserved(first_word(&escaped)) {
+        format!("\\{}", escaped)
+    } else {
+        escaped
+    }
+}
+
+/// Whether `word` at the start of a value would be read as a field.
+fn is_reserved(word: &str) -> bool {
+    word == "x" || take_priority(word).is_some() || DateTime::parse_date(word).is_some()
+}
+
+fn first_word(s: &str) -> &str {
+    s.split(' ').next().unwrap_or_default()
+}
+
+/// Reverses `escape` in a single pass, so that `\\n` stays a backslash
+/// followed by `n`. Any other backslash is kept as it is.
+fn unescape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some('\\') => out.push('\\'),
+            Some(c) => {
+                out.push('\\');
+                out.push(c);
+            }
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+fn take_priority(s: &str) -> Option<(char, &str)> {
+    let (word, rest) = s.split_once(' ').unwrap_or((s, ""));
+    let p = word
+        .strip_prefix('(')?
+        .strip_suffix(')')
+        .and_then(single_letter)?;
+    Some((p, rest))
+}
+
+fn take_date(s: &str) -> Option<(f64, &str)> {
+    let (word, rest) = s.split_once(' ').unwrap_or((s, ""));
+    let date = DateTime::parse_date(word)?;
+    Some((date.to_millis(), rest))
+}
+
+fn single_letter(s: &str) -> Option<char> {
+    let mut chars = s.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) if c.is_ascii_uppercase() => Some(c),
+        _ => None,
+    }
+}
+
+fn priority_of(rank: TaskRank) -> char {
+    match rank {
+        TaskRank::Primary => 'A',
+        TaskRank::Secondary => 'B',
+        TaskRank::Other => 'C',
+    }
+}
+
+fn rank_of(priority: Option<char>) -> TaskRank {
+    match priority {
+        Some('A') => TaskRank::Primary,
+        Some('B') => TaskRank::Secondary,
+        _ => TaskRank::Other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{test_board, test_contents};
+    use super::*;
+
+    #[test]
+    fn values_round_trip() {
+        let db = test_board(&[
+            (TaskRank::Primary, r"open C:\new folder\notes.txt", false),
+            (TaskRank::Primary, "first line\nsecond line\\", false),
+            (TaskRank::Secondary, r"a literal \n and \\n", true),
+            (
+                TaskRank::Other,
+                "call mom +family @phone due:2024-05-01",
+                false,
+            ),
+            (TaskRank::Other, "spaces  kept +a/b @home key:value", true),
+        ]);
+
+        let text = to_todotxt(&db);
+        assert_eq!(text.lines().count(), 5);
+        let read = from_todotxt(&text, 0.0).unwrap();
+        assert_eq!(test_contents(&read), test_contents(&db));
+    }
+
+    #[test]
+    fn lines_are_written_with_priorities_dates_and_escapes() {
+        let db = test_board(&[
+            (TaskRank::Primary, r"C:\new", false),
+            (TaskRank::Secondary, "two\nlines", true),
+        ]);
+
+        assert_eq!(
+            to_todotxt(&db),
+            "(A) 1970-01-02 C:\\\\new\nx 1970-01-03 1970-01-02 two\\nlines pri:B\n"
+        );
+    }
+
+    #[test]
+    fn leading_fields_in_values_are_escaped() {
+        let mut db = DataBase::default();
+        for (rank, value, done) in [
+            (TaskRank::Primary, "2024-01-01 call", false),
+            (TaskRank::Primary, "2024-01-01 called", true),
+            (TaskRank::Secondary, "(A) not a priority", true),
+            (TaskRank::Secondary, "x not finished", false),
+            (TaskRank::Other, r"\2024-01-01 after a backslash", false),
+        ] {
+            // without a creation date, nothing comes before the value
+            let mut task = TaskData::new(rank, value);
+            task.done = done;
+            db.insert_task(task);
+        }
+
+        let text = to_todotxt(&db);
+        assert!(text.starts_with("(A) \\2024-01-01 call\n"), "{}", text);
+        let read = from_todotxt(&text, 5.0).unwrap();
+        assert_eq!(test_contents(&read), test_contents(&db));
+        // created when read, not on the date in the value
+        assert!(read.tasks().all(|task| task.created_at == Some(5.0)));
+    }
+
+    #[test]
+    fn a_creation_date_is_not_read_as_the_completion() {
+        let mut task = TaskData::new(TaskRank::Primary, "done");
+        task.done = true;
+        task.created_at = DateTime::parse_date("2024-01-02").map(|d| d.to_millis());
+        let mut db = DataBase::default();
+        db.insert_task(task.clone());
+
+        let read = from_todotxt(&to_todotxt(&db), 0.0).unwrap();
+        let read = &read.get_by_rank(TaskRank::Primary)[0];
+        assert!(read.done);
+        assert_eq!(read.created_at, task.created_at);
+        assert_eq!(read.completed_at, task.created_at);
+    }
+
+    #[test]
+    fn spaces_around_values_are_kept() {
+        let db = test_board(&[
+            (TaskRank::Primary, "  indented", false),
+            (TaskRank::Primary, "trailing  ", false),
+            (TaskRank::Secondary, "  both  ", true),
+            (TaskRank::Other, " ", true),
+        ]);
+
+        let read = from_todotxt(&to_todotxt(&db), 0.0).unwrap();
+        assert_eq!(test_contents(&read), test_contents(&db));
+    }
+
+    #[test]
+    fn only_the_written_rank_tag_is_read() {
+        let db = test_board(&[
+            (TaskRank::Primary, "ask pri:C about it", true),
+            (TaskRank::Secondary, "ends with pri:A", true),
+            (TaskRank::Other, "pri:A", false),
+        ]);
+
+        let text = to_todotxt(&db);
+        assert!(text.contains("x 1970-01-03 1970-01-02 ends with pri:A pri:B\n"));
+        let read = from_todotxt(&text, 0.0).unwrap();
+        assert_eq!(test_contents(&read), test_contents(&db));
+    }
+
+    #[test]
+    fn lines_of_other_tools_are_read() {
+        let text = "(B) 2024-01-02 write +report @office due:2024-01-10\n\
+                    x (A) 2024-01-05 2024-01-01 done already\n\
+                    \n\
+                    x 2024-01-05 burned pri:A\n\
+                    plain C:\\Users\\me\n";
+        let db = from_todotxt(text, 0.0).unwrap();
+
+        assert_eq!(
+            test_contents(&db),
+            [
+                (TaskRank::Primary, "done already".to_owned(), true),
+                (TaskRank::Primary, "burned".to_owned(), true),
+                (
+                    TaskRank::Secondary,
+                    "write +report @office due:2024-01-10".to_owned(),
+                    false
+                ),
+                (TaskRank::Other, r"plain C:\Users\me".to_owned(), false),
+            ]
+        );
+        let created_at = db.get_by_rank(TaskRank::Secondary)[0].created_at;
+        assert_eq!(
+            created_at,
+            DateTime::parse_date("2024-01-02").map(|d| d.to_millis())
+        );
+    }
+}
@@ -0,0 +1,22 @@
+//! The task data model of Burner List, without any dependency on the browser.
+//!
+//! A board is a `DataBase` of `TaskData`, each in one of the `TaskRank`
+//! columns. Every change is a `TaskEvent` applied with
+//! `DataBase::apply_event`, and copies of a board edited elsewhere are
+//! combined with `DataBase::merge`. `DataBase` serializes to JSON in a
+//! versioned format and reads every earlier version; `format` has the
+//! plain-text formats other tools use.
+
+mod database;
+mod event;
+pub mod format;
+mod position;
+mod stamp;
+mod taskdata;
+mod taskrank;
+
+pub use database::*;
+pub use event::*;
+pub use stamp::*;
+pub use taskdata::*;
+pub use taskrank::*;
@@ -0,0 +1,79 @@
+//! Fractional indices ordering the tasks within a rank. A position is a
+//! base-62 fraction written without the leading `0.` and never ends with `0`,
+//! so that there is always room for another position between two of them.
+
+const DIGITS: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
+/// Returns a position that sorts strictly between `before` and `after`. `None`
+/// stands for the start and the end of the list respectively.
+pub(super) fn between(before: Option<&str>, after: Option<&str>) -> String {
+    let before = before.unwrap_or("").as_bytes();
+    match after.map(str::as_bytes) {
+        Some(after) if before < after => midpoint(before, Some(after)),
+        _ => increment(before),
+    }
+}
+
+/// Returns `n` increasing positions.
+pub(super) fn sequence(n: usize) -> Vec<String> {
+    let mut positions = Vec::<String>::with_capacity(n);
+    for _ in 0..n {
+        let last = positions.last().map(String::as_str);
+        positions.push(between(last, None));
+    }
+    positions
+}
+
+fn digit(c: u8) -> usize {
+    DIGITS.iter().position(|&d| d == c).unwrap_or(0)
+}
+
+fn to_string(digits: &[u8]) -> String {
+    String::from_utf8_lossy(digits).into_owned()
+}
+
+/// Smallest short position after `before`: bumps the first digit that can be
+/// bumped and drops the rest.
+fn increment(before: &[u8]) -> String {
+    for (i, &c) in before.iter().enumerate() {
+        let d = digit(c);
+        if d + 1 < DIGITS.len() {
+            return format!("{}{}", to_string(&before[..i]), DIGITS[d + 1] as char);
+        }
+    }
+    format!("{}{}", to_string(before), DIGITS[DIGITS.len() / 2] as char)
+}
+
+fn midpoint(before: &[u8], after: Option<&[u8]>) -> String {
+    if let Some(after) = after {
+        // copy the common prefix, reading missing digits of `before` as zeros
+        let n = after
+            .iter()
+            .enumerate()
+            .take_while(|&(i, &c)| before.get(i).copied().unwrap_or(DIGITS[0]) == c)
+            .count();
+        if n > 0 {
+            let rest = before.get(n..).unwrap_or_default();
+            return format!(
+                "{}{}",
+                to_string(&after[..n]),
+                midpoint(rest, Some(&after[n..]))
+            );
+        }
+    }
+
+    let low = before.first().map(|&c| digit(c)).unwrap_or(0);
+    let high = after
+        .and_then(|after| after.first())
+        .map(|&c| digit(c))
+        .unwrap_or(DIGITS.len());
+    if high - low > 1 {
+        (DIGITS[(low + high) / 2] as char).to_string()
+    } else if let Some(after) = after.filter(|after| after.len() > 1) {
+        // `after` is longer, so its first digit alone is already smaller
+        (after[0] as char).to_string()
+    } else {
+        let rest = before.get(1..).unwrap_or_default();
+        format!("{}{}", DIGITS[low] as char, midpoint(rest, None))
+    }
+}
@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Hybrid logical clock value attached to every CRDT register. Stamps are
+/// totally ordered: by time, then by the counter that breaks ties within the
+/// same millisecond, then by the replica that issued them.
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+pub struct Stamp {
+    /// Milliseconds since the UNIX epoch.
+    pub time: u64,
+    pub counter: u32,
+    pub replica: Uuid,
+}
+
+impl Stamp {
+    /// Issues a stamp for `replica` at `timestamp` that is greater than `last`,
+    /// even if the wall clock went backwards.
+    pub fn next(last: Stamp, timestamp: f64, replica: Uuid) -> Self {
+        let time = timestamp.max(0.0) as u64;
+        if time > last.time {
+            Stamp {
+                time,
+                counter: 0,
+                replica,
+            }
+        } else {
+            Stamp {
+                time: last.time,
+                counter: last.counter + 1,
+                replica,
+            }
+        }
+    }
+
+    /// Whether this is `Stamp::default()`, which orders before every issued
+    /// stamp.
+    pub fn is_zero(&self) -> bool {
+        *self == Stamp::default()
+    }
+}
@@ -0,0 +1,124 @@
+use crate::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TaskData {
+    id: Uuid,
+    pub rank: TaskRank,
+    pub value: String,
+    #[serde(default)]
+    pub done: bool,
+    /// Milliseconds since the UNIX epoch.
+    #[serde(default)]
+    pub completed_at: Option<f64>,
+    /// Milliseconds since the UNIX epoch. `None` for tasks saved before
+    /// timestamps were recorded.
+    #[serde(default)]
+    pub created_at: Option<f64>,
+    /// Last time the value was edited.
+    #[serde(default)]
+    pub edited_at: Option<f64>,
+    /// Last time the task was moved to another rank.
+    #[serde(default)]
+    pub moved_at: Option<f64>,
+    /// Fractional index ordering the task within its rank.
+    #[serde(default)]
+    pub(crate) position: String,
+    #[serde(default)]
+    pub(crate) stamps: TaskStamps,
+}
+
+/// Stamps of the last write to each last-writer-wins register of a task.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct TaskStamps {
+    /// `value` and `edited_at`.
+    pub value: Stamp,
+    /// `rank`, `position` and `moved_at`.
+    pub rank: Stamp,
+    /// `done` and `completed_at`.
+    pub done: Stamp,
+    /// Last time the task was restored after being deleted or burned. A task
+    /// is on the board while this is newer than its tombstone.
+    #[serde(default, skip_serializing_if = "Stamp::is_zero")]
+    pub restored: Stamp,
+}
+
+impl TaskData {
+    pub fn new(rank: TaskRank, value: &str) -> Self {
+        Self::with_id(Uuid::new_v4(), rank, value)
+    }
+
+    pub fn with_id(id: Uuid, rank: TaskRank, value: &str) -> Self {
+        TaskData {
+            id,
+            rank,
+            value: value.to_owned(),
+            done: false,
+            completed_at: None,
+            created_at: None,
+            edited_at: None,
+            moved_at: None,
+            position: String::new(),
+            stamps: TaskStamps::default(),
+        }
+    }
+
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    /// Fractional index ordering the task within its rank.
+    pub fn position(&self) -> &str {
+        &self.position
+    }
+
+    pub fn stamps(&self) -> TaskStamps {
+        self.stamps
+    }
+
+    /// Copy of the task under a new id.
+    pub(crate) fn with_new_id(&self) -> TaskData {
+        TaskData {
+            id: Uuid::new_v4(),
+            ..self.clone()
+        }
+    }
+
+    /// Takes every register of `other` that was written after ours. A copy
+    /// restored later replaces ours as a whole, and an older one is ignored,
+    /// since its registers were written before the task was deleted.
+    pub(crate) fn merge(&mut self, other: &TaskData) {
+        if other.stamps.restored != self.stamps.restored {
+            if other.stamps.restored > self.stamps.restored {
+                *self = other.clone();
+            }
+            return;
+        }
+        if other.stamps.value > self.stamps.value {
+            self.value = other.value.clone();
+            self.edited_at = other.edited_at;
+            self.stamps.value = other.stamps.value;
+        }
+        if other.stamps.rank > self.stamps.rank {
+            self.rank = other.rank;
+            self.position = other.position.clone();
+            self.moved_at = other.moved_at;
+            self.stamps.rank = other.stamps.rank;
+        }
+        if other.stamps.done > self.stamps.done {
+            self.done = other.done;
+            self.completed_at = other.completed_at;
+            self.stamps.done = other.stamps.done;
+        }
+        if self.created_at.is_none() {
+            self.created_at = other.created_at;
+        }
+    }
+}
+
+impl PartialEq for TaskData {
+    fn eq(&self, other: &Self) -> bool {
+        self.id.eq(&other.id)
+    }
+}
@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TaskRank {
+    Primary,
+    Secondary,
+    Other,
+}
+
+impl TaskRank {
+    /// Every rank, in the order the columns are laid out.
+    pub const ALL: [TaskRank; 3] = [TaskRank::Primary, TaskRank::Secondary, TaskRank::Other];
+}
+
+impl fmt::Display for TaskRank {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            TaskRank::Primary => "Primary",
+            TaskRank::Secondary => "Secondary",
+            TaskRank::Other => "Other",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Parses the name written by `Display`, ignoring case.
+impl FromStr for TaskRank {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        TaskRank::ALL
+            .into_iter()
+            .find(|rank| rank.to_string().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| format!("unknown rank `{}`", s))
+    }
+}
@@ -0,0 +1,356 @@
+//! Replicas that apply random events and merge each other's copies in any
+//! order, any number of times, must end up with the same board.
+
+use burner_list_core::*;
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+const SEEDS: u64 = 200;
+const REPLICAS: usize = 3;
+const ROUNDS: usize = 4;
+const EVENTS_PER_ROUND: usize = 8;
+
+/// xorshift64*, so that a failing seed can be replayed.
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        Rng(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1)
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+
+    fn chance(&mut self, percent: u64) -> bool {
+        self.next() % 100 < percent
+    }
+
+    fn rank(&mut self) -> TaskRank {
+        TaskRank::ALL[self.below(TaskRank::ALL.len())]
+    }
+
+    fn uuid(&mut self) -> Uuid {
+        Uuid::from_u128((u128::from(self.next()) << 64) | u128::from(self.next()))
+    }
+}
+
+/// An empty board issuing stamps as `replica`.
+fn replica(replica: Uuid) -> DataBase {
+    serde_json::from_value(json!({
+        "version": 4,
+        "replica": replica,
+        "clock": Stamp::default(),
+        "tombstones": {},
+        "tasks": [],
+    }))
+    .unwrap()
+}
+
+/// Everything but the replica: the tasks of every rank in order with all of
+/// their fields, the tombstones and the clock.
+fn contents(db: &DataBase) -> Value {
+    let mut value = serde_json::to_value(db).unwrap();
+    value.as_object_mut().unwrap().remove("replica");
+    value
+}
+
+fn assert_ordered(db: &DataBase) {
+    for rank in TaskRank::ALL {
+        let keys = db
+            .get_by_rank(rank)
+            .into_iter()
+            .map(|task| (task.position().to_owned(), task.id()))
+            .collect::<Vec<_>>();
+        assert!(keys.windows(2).all(|w| w[0] < w[1]), "{:?}", keys);
+    }
+}
+
+/// A random event, mostly about the tasks `db` knows of, sometimes about a
+/// task it has never seen.
+fn random_event(rng: &mut Rng, db: &DataBase) -> TaskEvent {
+    let ids = db.tasks().map(TaskData::id).collect::<Vec<_>>();
+    let id = if !ids.is_empty() && rng.chance(90) {
+        ids[rng.below(ids.len())]
+    } else {
+        rng.uuid()
+    };
+
+    match rng.below(10) {
+        0 | 1 => TaskEvent::Add(TaskAddData {
+            id: rng.uuid(),
+            rank: rng.rank(),
+            value: format!("task {}", rng.below(100)),
+        }),
+        2 => TaskEvent::Edit(TaskEditData {
+            id,
+            value: format!("edited {}", rng.below(100)),
+        }),
+        3 | 4 => TaskEvent::Move(TaskMoveData {
+            id,
+            rank: rng.rank(),
+            index: if rng.chance(80) {
+                Some(rng.below(4))
+            } else {
+                None
+            },
+        }),
+        5 => TaskEvent::Delete(TaskDeleteData { id }),
+        6 => TaskEvent::Complete(TaskCompleteData { id }),
+        7 => TaskEvent::Uncomplete(TaskUncompleteData { id }),
+        8 => TaskEvent::Burn(TaskBurnData {
+            rank: rng.rank(),
+            carry_over: rng.chance(50),
+        }),
+        _ => TaskEvent::Swap(TaskSwapData {
+            rank1: rng.rank(),
+            rank2: rng.rank(),
+        }),
+    }
+}
+
+fn permutations(n: usize) -> Vec<Vec<usize>> {
+    if n == 0 {
+        return vec![vec![]];
+    }
+    let mut all = vec![];
+    for rest in permutations(n - 1) {
+        for at in 0..=rest.len() {
+            let mut order = rest.clone();
+            order.insert(at, n - 1);
+            all.push(order);
+        }
+    }
+    all
+}
+
+/// Lets `REPLICAS` replicas of a shared board edit it concurrently, with some
+/// merges between them along the way. Returns the final copies.
+fn diverge(rng: &mut Rng) -> Vec<DataBase> {
+    let mut base = replica(rng.uuid());
+    for _ in 0..6 {
+        let event = random_event(rng, &base);
+        base.apply_event(&event, 1.0);
+    }
+
+    let mut replicas = (0..REPLICAS)
+        .map(|_| {
+            let mut db = replica(rng.uuid());
+            db.merge(&base);
+            db
+        })
+        .collect::<Vec<_>>();
+
+    // deleted and burned tasks, which any replica may restore
+    let mut removed = vec![];
+    for round in 0..ROUNDS {
+        for db in &mut replicas {
+            for _ in 0..EVENTS_PER_ROUND {
+                // a handful of milliseconds, so that stamps often share a time
+                let timestamp = (round * 2 + rng.below(3)) as f64;
+                if !removed.is_empty() && rng.chance(10) {
+                    let task: &TaskData = &removed[rng.below(removed.len())];
+                    db.restore_task(task, rng.rank(), timestamp);
+                    continue;
+                }
+
+                let event = random_event(rng, db);
+                let before = db.clone();
+                db.apply_event(&event, timestamp);
+                removed.extend(
+                    before
+                        .tasks()
+                        .filter(|task| db.get_by_id(task.id()).is_none())
+                        .cloned(),
+                );
+            }
+        }
+
+        // some replicas hear from some others, some changes are sent twice
+        for _ in 0..rng.below(REPLICAS + 1) {
+            let (from, to) = (rng.below(REPLICAS), rng.below(REPLICAS));
+            let changes = if rng.chance(50) {
+                replicas[from].clone()
+            } else {
+                match replicas[from].changes_since(None) {
+                    Some(changes) => changes,
+                    None => continue,
+                }
+            };
+            replicas[to].merge(&changes);
+        }
+    }
+
+    replicas
+}
+
+#[test]
+fn merging_in_any_order_converges() {
+    for seed in 0..SEEDS {
+        let mut rng = Rng::new(seed);
+        let replicas = diverge(&mut rng);
+
+        let mut expected = None;
+        for start in 0..REPLICAS {
+            for order in permutations(REPLICAS) {
+                let mut db = replicas[start].clone();
+                for &i in &order {
+                    db.merge(&replicas[i]);
+                }
+                // merging again, or merging itself, changes nothing
+                let before = contents(&db);
+                db.merge(&replicas[order[0]]);
+                db.merge(&db.clone());
+                assert_eq!(contents(&db), before, "seed {}: not idempotent", seed);
+
+                assert_ordered(&db);
+                let contents = contents(&db);
+                match &expected {
+                    None => expected = Some(contents),
+                    Some(expected) => assert_eq!(
+                        &contents, expected,
+                        "seed {}: start {}, order {:?}",
+                        seed, start, order
+                    ),
+                }
+            }
+        }
+
+        // grouping does not matter either: (0 + 1) + 2 == 0 + (1 + 2)
+        let mut left = replicas[0].clone();
+        left.merge(&replicas[1]);
+        left.merge(&replicas[2]);
+        let mut right = replicas[1].clone();
+        right.merge(&replicas[2]);
+        let mut grouped = replicas[0].clone();
+        grouped.merge(&right);
+        assert_eq!(contents(&left), contents(&grouped), "seed {}", seed);
+    }
+}
+
+#[test]
+fn a_restored_task_outlives_its_deletion() {
+    let mut rng = Rng::new(0);
+    let id = rng.uuid();
+    let mut a = replica(rng.uuid());
+    a.apply_event(
+        &TaskEvent::Add(TaskAddData {
+            id,
+            rank: TaskRank::Secondary,
+            value: "task".to_owned(),
+        }),
+        1.0,
+    );
+    let mut b = replica(rng.uuid());
+    b.merge(&a);
+
+    // b edits the task while a deletes and restores it
+    let task = a.get_by_id(id).unwrap().clone();
+    assert!(a.apply_event(&TaskEvent::Delete(TaskDeleteData { id }), 2.0));
+    assert!(b.apply_event(
+        &TaskEvent::Edit(TaskEditData {
+            id,
+            value: "edited".to_owned(),
+        }),
+        3.0,
+    ));
+    assert!(a.restore_task(&task, task.rank, 2.0));
+    assert!(!a.restore_task(&task, task.rank, 2.0));
+
+    let mut ab = a.clone();
+    ab.merge(&b);
+    let mut ba = b.clone();
+    ba.merge(&a);
+    assert_eq!(contents(&ab), contents(&ba));
+    let restored = ab.get_by_id(id).expect("the task to be restored");
+    assert_eq!(restored.value, "task");
+    assert_eq!(restored.rank, TaskRank::Secondary);
+    assert_eq!(restored.position(), task.position());
+    assert_eq!(restored.created_at, task.created_at);
+
+    // or into another rank, after its tasks
+    let mut c = ab.clone();
+    c.apply_event(&TaskEvent::Delete(TaskDeleteData { id }), 4.0);
+    c.apply_event(
+        &TaskEvent::Add(TaskAddData {
+            id: rng.uuid(),
+            rank: TaskRank::Other,
+            value: "other".to_owned(),
+        }),
+        4.0,
+    );
+    assert!(c.restore_task(&task, TaskRank::Other, 5.0));
+    let other = c.get_by_rank(TaskRank::Other);
+    assert_eq!(other.len(), 2);
+    assert_eq!(other[1].id(), id);
+    assert_eq!(other[1].moved_at, Some(5.0));
+    assert_eq!(other[1].created_at, task.created_at);
+
+    // deleting it again deletes it everywhere
+    assert!(ba.apply_event(&TaskEvent::Delete(TaskDeleteData { id }), 4.0));
+    ab.merge(&ba);
+    assert!(ab.get_by_id(id).is_none());
+}
+
+#[test]
+fn concurrent_moves_to_the_same_place_keep_a_total_order() {
+    let mut rng = Rng::new(0);
+    let mut base = replica(rng.uuid());
+    let ids = (0..4).map(|_| rng.uuid()).collect::<Vec<_>>();
+    for (i, &id) in ids.iter().enumerate() {
+        base.apply_event(
+            &TaskEvent::Add(TaskAddData {
+                id,
+                rank: TaskRank::Primary,
+                value: format!("task {}", i),
+            }),
+            1.0,
+        );
+    }
+
+    let mut a = replica(rng.uuid());
+    a.merge(&base);
+    let mut b = replica(rng.uuid());
+    b.merge(&base);
+
+    // both replicas move a different task to the top of the same rank and
+    // pick the same position for it
+    let move_to = |db: &mut DataBase, id, index| {
+        db.apply_event(
+            &TaskEvent::Move(TaskMoveData {
+                id,
+                rank: TaskRank::Secondary,
+                index: Some(index),
+            }),
+            2.0,
+        )
+    };
+    assert!(move_to(&mut a, ids[0], 0));
+    assert!(move_to(&mut b, ids[1], 0));
+    let mut merged = a.clone();
+    merged.merge(&b);
+    let tied = merged.get_by_rank(TaskRank::Secondary);
+    assert_eq!(tied.len(), 2);
+    assert_eq!(tied[0].position(), tied[1].position());
+
+    // then both insert between the tied tasks, i.e. between(p, p)
+    a.merge(&b);
+    b.merge(&a);
+    assert!(move_to(&mut a, ids[2], 1));
+    assert!(move_to(&mut b, ids[3], 1));
+
+    let mut ab = a.clone();
+    ab.merge(&b);
+    let mut ba = b.clone();
+    ba.merge(&a);
+    assert_eq!(contents(&ab), contents(&ba));
+    assert_ordered(&ab);
+    assert_eq!(ab.get_by_rank(TaskRank::Secondary).len(), 4);
+}
@@ -0,0 +1,91 @@
+//! Burns and swaps that would not move a task leave the board as it was and
+//! report that nothing changed, so that they are neither logged nor undone.
+
+use burner_list_core::*;
+use uuid::Uuid;
+
+fn board() -> DataBase {
+    let mut db = DataBase::default();
+    for (rank, value, done) in [
+        (TaskRank::Primary, "open", false),
+        (TaskRank::Primary, "done", true),
+        (TaskRank::Secondary, "later", false),
+    ] {
+        let id = Uuid::new_v4();
+        let value = value.to_owned();
+        db.apply_event(&TaskEvent::Add(TaskAddData { id, rank, value }), 1.0);
+        if done {
+            db.apply_event(&TaskEvent::Complete(TaskCompleteData { id }), 1.0);
+        }
+    }
+    db
+}
+
+fn unchanged(db: &mut DataBase, event: TaskEvent) {
+    let before = serde_json::to_value(&*db).unwrap();
+    assert!(!db.apply_event(&event, 2.0), "{:?}", event);
+    assert_eq!(serde_json::to_value(&*db).unwrap(), before, "{:?}", event);
+}
+
+#[test]
+fn burning_nothing_changes_nothing() {
+    let mut db = board();
+    unchanged(
+        &mut db,
+        TaskEvent::Burn(TaskBurnData {
+            rank: TaskRank::Other,
+            carry_over: false,
+        }),
+    );
+    // only unfinished tasks, which are carried over
+    unchanged(
+        &mut db,
+        TaskEvent::Burn(TaskBurnData {
+            rank: TaskRank::Secondary,
+            carry_over: true,
+        }),
+    );
+
+    assert!(db.apply_event(
+        &TaskEvent::Burn(TaskBurnData {
+            rank: TaskRank::Primary,
+            carry_over: true,
+        }),
+        2.0,
+    ));
+    let left = db.get_by_rank(TaskRank::Primary);
+    assert_eq!(left.len(), 1);
+    assert_eq!(left[0].value, "open");
+}
+
+#[test]
+fn swapping_a_rank_with_itself_or_two_empty_ranks_changes_nothing() {
+    let mut db = board();
+    unchanged(
+        &mut db,
+        TaskEvent::Swap(TaskSwapData {
+            rank1: TaskRank::Primary,
+            rank2: TaskRank::Primary,
+        }),
+    );
+
+    let mut empty = DataBase::default();
+    unchanged(
+        &mut empty,
+        TaskEvent::Swap(TaskSwapData {
+            rank1: TaskRank::Primary,
+            rank2: TaskRank::Other,
+        }),
+    );
+
+    // one empty rank is enough to move the tasks of the other
+    assert!(db.apply_event(
+        &TaskEvent::Swap(TaskSwapData {
+            rank1: TaskRank::Secondary,
+            rank2: TaskRank::Other,
+        }),
+        2.0,
+    ));
+    assert!(db.get_by_rank(TaskRank::Secondary).is_empty());
+    assert_eq!(db.get_by_rank(TaskRank::Other)[0].value, "later");
+}
@@ -0,0 +1,100 @@
+use burner_list_core::{DataBase, TaskRank};
+use serde_json::Value;
+
+/// The same board as saved by every schema version, oldest first. `v0.json`
+/// is the unversioned layout of the first release.
+const FIXTURES: [(&str, &str); 5] = [
+    ("v0", include_str!("fixtures/v0.json")),
+    ("v1", include_str!("fixtures/v1.json")),
+    ("v2", include_str!("fixtures/v2.json")),
+    ("v3", include_str!("fixtures/v3.json")),
+    ("v4", include_str!("fixtures/v4.json")),
+];
+
+fn load(name: &str, raw: &str) -> DataBase {
+    serde_json::from_str(raw).unwrap_or_else(|e| panic!("{}: {}", name, e))
+}
+
+/// The serialized board without the replica, which a migrated copy picks at
+/// random.
+fn contents(db: &DataBase) -> Value {
+    let mut value = serde_json::to_value(db).unwrap();
+    value.as_object_mut().unwrap().remove("replica");
+    value
+}
+
+#[test]
+fn every_version_loads_into_the_same_board() {
+    let (name, raw) = FIXTURES[FIXTURES.len() - 1];
+    let expected = contents(&load(name, raw));
+
+    for (name, raw) in FIXTURES {
+        assert_eq!(contents(&load(name, raw)), expected, "{}", name);
+    }
+}
+
+#[test]
+fn v0_keeps_ids_order_and_values() {
+    let (name, raw) = FIXTURES[0];
+    let db = load(name, raw);
+
+    let values = |rank| {
+        db.get_by_rank(rank)
+            .into_iter()
+            .map(|task| (task.id().to_string(), task.value.clone(), task.done))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        values(TaskRank::Primary),
+        [
+            (
+                "3f2b8c1e-6a4d-4c55-9b1e-0d7f2a9e4c11".to_owned(),
+                "メールを返す".to_owned(),
+                false
+            ),
+            (
+                "a1c9e7d2-2b3f-4e6a-8c5d-7f1e0b2a3c44".to_owned(),
+                "Write the release notes".to_owned(),
+                false
+            ),
+        ]
+    );
+    assert_eq!(
+        values(TaskRank::Secondary),
+        [(
+            "5d8e2f7a-9c1b-4a3e-b6d4-2e8f1c0a7b55".to_owned(),
+            "部屋の掃除".to_owned(),
+            false
+        )]
+    );
+    assert_eq!(
+        values(TaskRank::Other),
+        [
+            (
+                "c7b4a2e9-1f3d-4b8c-a5e6-9d2c0f1e8a66".to_owned(),
+                "Read \"The Rust Book\"\nchapter 4".to_owned(),
+                false
+            ),
+            (
+                "e9f1d3b5-7a2c-4e6b-8d0f-1c3a5e7b9d77".to_owned(),
+                "🔥 これを燃やす".to_owned(),
+                false
+            ),
+        ]
+    );
+}
+
+#[test]
+fn current_version_round_trips() {
+    let (name, raw) = FIXTURES[FIXTURES.len() - 1];
+    let expected = serde_json::from_str::<Value>(raw).unwrap();
+
+    assert_eq!(serde_json::to_value(load(name, raw)).unwrap(), expected);
+}
+
+#[test]
+fn newer_version_is_rejected() {
+    let raw = r#"{ "version": 5, "tasks": [] }"#;
+
+    assert!(serde_json::from_str::<DataBase>(raw).is_err());
+}
@@ -0,0 +1,250 @@
+//! Reference server of the sync protocol described in `README.md`.
+//!
+//! It keeps the changes pushed to every board in memory, optionally persisted
+//! to a JSON file, and never looks inside them: merging is up to the clients.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::PathBuf;
+use std::process::exit;
+use tiny_http::{Header, Method, Request, Response, Server};
+use uuid::Uuid;
+
+const USAGE: &str = "usage: burner-list-server [--addr ADDR] [--token TOKEN] [--data FILE]";
+
+/// Largest change accepted, well above what fits in a browser's local storage.
+const MAX_BODY_BYTES: u64 = 16 * 1024 * 1024;
+
+struct Config {
+    addr: String,
+    /// Clients must send `Authorization: Bearer <token>` when set.
+    token: Option<String>,
+    /// File the boards are saved to after every push.
+    data: Option<PathBuf>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct Change {
+    cursor: u64,
+    id: Uuid,
+    client: Uuid,
+    db: Value,
+}
+
+#[derive(Deserialize)]
+struct PushRequest {
+    id: Uuid,
+    client: Uuid,
+    db: Value,
+}
+
+#[derive(Serialize)]
+struct PushResponse {
+    cursor: u64,
+}
+
+#[derive(Serialize)]
+struct PullResponse<'a> {
+    cursor: u64,
+    changes: &'a [Change],
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct Boards {
+    boards: HashMap<Uuid, Vec<Change>>,
+}
+
+impl Boards {
+    fn load(config: &Config) -> Self {
+        let path = match &config.data {
+            Some(path) if path.exists() => path,
+            _ => return Self::default(),
+        };
+        match std::fs::read_to_string(path).map(|raw| serde_json::from_str(&raw)) {
+            Ok(Ok(boards)) => boards,
+            Ok(Err(e)) => fail(&format!("{}: {}", path.display(), e)),
+            Err(e) => fail(&format!("{}: {}", path.display(), e)),
+        }
+    }
+
+    fn save(&self, config: &Config) {
+        if let Some(path) = &config.data {
+            // write a temporary file first so that a crash keeps the old data
+            let tmp = path.with_extension("tmp");
+            let result = serde_json::to_string(self)
+                .map_err(|e| e.to_string())
+                .and_then(|raw| std::fs::write(&tmp, raw).map_err(|e| e.to_string()))
+                .and_then(|_| std::fs::rename(&tmp, path).map_err(|e| e.to_string()));
+            if let Err(e) = result {
+                eprintln!("failed to save {}: {}", path.display(), e);
+            }
+        }
+    }
+
+    /// Appends the change unless a change with the same id was already
+    /// pushed. Returns the cursor of the change.
+    fn push(&mut self, board: Uuid, request: PushRequest) -> u64 {
+        let changes = self.boards.entry(board).or_default();
+        if let Some(change) = changes.iter().find(|change| change.id == request.id) {
+            return change.cursor;
+        }
+
+        let cursor = changes.len() as u64 + 1;
+        changes.push(Change {
+            cursor,
+            id: request.id,
+            client: request.client,
+            db: request.db,
+        });
+        cursor
+    }
+
+    fn pull(&self, board: Uuid, since: u64) -> PullResponse<'_> {
+        let changes = self
+            .boards
+            .get(&board)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        let since = (since as usize).min(changes.len());
+        PullResponse {
+            cursor: changes.len() as u64,
+            changes: &changes[since..],
+        }
+    }
+}
+
+fn main() {
+    let config = parse_args();
+    let mut boards = Boards::load(&config);
+    let server = Server::http(&config.addr).unwrap_or_else(|e| fail(&e.to_string()));
+    // the actual address, in case port 0 was given
+    println!("listening on http://{}", server.server_addr());
+
+    for mut request in server.incoming_requests() {
+        let (status, body) = handle(&config, &mut boards, &mut request);
+        println!("{} {} -> {}", request.method(), request.url(), status);
+
+        let mut response = Response::from_string(body).with_status_code(status);
+        for (name, value) in [
+            ("Content-Type", "application/json"),
+            ("Access-Control-Allow-Origin", "*"),
+            ("Access-Control-Allow-Methods", "GET, POST, OPTIONS"),
+            (
+                "Access-Control-Allow-Headers",
+                "Authorization, Content-Type",
+            ),
+        ] {
+            response.add_header(Header::from_bytes(name, value).expect("valid header"));
+        }
+        if let Err(e) = request.respond(response) {
+            eprintln!("failed to respond: {}", e);
+        }
+    }
+}
+
+fn handle(config: &Config, boards: &mut Boards, request: &mut Request) -> (u16, String) {
+    if request.method() == &Method::Options {
+        return (204, String::new());
+    }
+    if !authorized(config, request) {
+        return error(401, "unauthorized");
+    }
+
+    let url = request.url().to_owned();
+    let (path, query) = url.split_once('?').unwrap_or((&url, ""));
+    let segments = path.trim_matches('/').split('/').collect::<Vec<_>>();
+    let board = match segments[..] {
+        ["boards", board, "changes"] => match Uuid::parse_str(board) {
+            Ok(board) => board,
+            Err(_) => return error(400, "invalid board id"),
+        },
+        _ => return error(404, "not found"),
+    };
+
+    match request.method() {
+        Method::Get => {
+            let since = query
+                .split('&')
+                .find_map(|pair| pair.strip_prefix("since="))
+                .map(str::parse::<u64>)
+                .unwrap_or(Ok(0));
+            match since {
+                Ok(since) => (200, json(&boards.pull(board, since))),
+                Err(_) => error(400, "invalid cursor"),
+            }
+        }
+        Method::Post => {
+            if request
+                .body_length()
+                .is_some_and(|length| length as u64 > MAX_BODY_BYTES)
+            {
+                return error(413, "request body too large");
+            }
+            let mut body = String::new();
+            let mut reader = request.as_reader().take(MAX_BODY_BYTES + 1);
+            if let Err(e) = reader.read_to_string(&mut body) {
+                return error(400, &e.to_string());
+            }
+            if body.len() as u64 > MAX_BODY_BYTES {
+                return error(413, "request body too large");
+            }
+            match serde_json::from_str::<PushRequest>(&body) {
+                Ok(push) => {
+                    let cursor = boards.push(board, push);
+                    boards.save(config);
+                    (200, json(&PushResponse { cursor }))
+                }
+                Err(e) => error(400, &e.to_string()),
+            }
+        }
+        _ => error(405, "method not allowed"),
+    }
+}
+
+fn authorized(config: &Config, request: &Request) -> bool {
+    let token = match &config.token {
+        Some(token) => token,
+        None => return true,
+    };
+    let expected = format!("Bearer {}", token);
+    request
+        .headers()
+        .iter()
+        .any(|header| header.field.equiv("Authorization") && header.value.as_str() == expected)
+}
+
+fn json<T: Serialize>(value: &T) -> String {
+    serde_json::to_string(value).expect("serializable response")
+}
+
+fn error(status: u16, message: &str) -> (u16, String) {
+    (status, json(&serde_json::json!({ "error": message })))
+}
+
+fn parse_args() -> Config {
+    let mut config = Config {
+        addr: "127.0.0.1:8787".to_owned(),
+        token: std::env::var("BURNER_LIST_TOKEN").ok(),
+        data: None,
+    };
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().unwrap_or_else(|| fail(USAGE));
+        match arg.as_str() {
+            "--addr" => config.addr = value(),
+            "--token" => config.token = Some(value()),
+            "--data" => config.data = Some(PathBuf::from(value())),
+            _ => fail(USAGE),
+        }
+    }
+
+    config
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("{}", message);
+    exit(1);
+}
@@ -0,0 +1,205 @@
+//! Runs the server on an ephemeral port and talks to it over plain HTTP.
+
+use serde_json::{json, Value};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpStream;
+use std::process::{Child, Command, Stdio};
+use std::time::Duration;
+
+const TOKEN: &str = "secret";
+
+/// The server process, killed when dropped.
+struct Server {
+    child: Child,
+    addr: String,
+}
+
+impl Server {
+    fn start() -> Self {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_burner-list-server"))
+            .args(["--addr", "127.0.0.1:0", "--token", TOKEN])
+            .env_remove("BURNER_LIST_TOKEN")
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("server to start");
+
+        let mut stdout = BufReader::new(child.stdout.take().unwrap());
+        let mut line = String::new();
+        stdout.read_line(&mut line).unwrap();
+        let addr = line
+            .trim()
+            .strip_prefix("listening on http://")
+            .unwrap_or_else(|| panic!("unexpected output: {}", line))
+            .to_owned();
+        // keep reading the request log so that the server never blocks on it
+        std::thread::spawn(move || std::io::copy(&mut stdout, &mut std::io::sink()));
+
+        Server { child, addr }
+    }
+
+    /// Sends a request and returns the status and the JSON body.
+    fn request(
+        &self,
+        method: &str,
+        path: &str,
+        token: Option<&str>,
+        body: Option<&Value>,
+    ) -> (u16, Value) {
+        let body = body.map(Value::to_string).unwrap_or_default();
+        let mut head = format!(
+            "{} {} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\nContent-Length: {}\r\n",
+            method,
+            path,
+            self.addr,
+            body.len()
+        );
+        if let Some(token) = token {
+            head += &format!("Authorization: Bearer {}\r\n", token);
+        }
+        self.send(&format!("{}\r\n{}", head, body))
+    }
+
+    fn send(&self, raw: &str) -> (u16, Value) {
+        let mut stream = TcpStream::connect(&self.addr).unwrap();
+        stream
+            .set_read_timeout(Some(Duration::from_secs(10)))
+            .unwrap();
+        stream.write_all(raw.as_bytes()).unwrap();
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        let (head, body) = response.split_once("\r\n\r\n").expect("a response");
+        let status = head
+            .split(' ')
+            .nth(1)
+            .and_then(|status| status.parse().ok())
+            .unwrap_or_else(|| panic!("unexpected response: {}", head));
+        let body = if body.is_empty() {
+            Value::Null
+        } else {
+            serde_json::from_str(body).unwrap()
+        };
+        (status, body)
+    }
+
+    fn push(&self, board: &str, change: &Value) -> (u16, Value) {
+        let path = format!("/boards/{}/changes", board);
+        self.request("POST", &path, Some(TOKEN), Some(change))
+    }
+
+    fn pull(&self, board: &str, since: u64) -> (u16, Value) {
+        let path = format!("/boards/{}/changes?since={}", board, since);
+        self.request("GET", &path, Some(TOKEN), None)
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+const BOARD: &str = "0f6a3c1e-8d2b-4e7a-9c5f-1b3d5e7f9a20";
+const CLIENT: &str = "6c1e9a7d-3b5f-4d2e-8a0c-2e4f6a8b0c31";
+
+fn change(id: &str, value: &str) -> Value {
+    json!({ "id": id, "client": CLIENT, "db": { "value": value } })
+}
+
+fn pulled_ids(response: &Value) -> Vec<&str> {
+    response["changes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|change| change["id"].as_str().unwrap())
+        .collect()
+}
+
+#[test]
+fn pushed_changes_are_pulled_after_the_cursor() {
+    let server = Server::start();
+    let first = change("a6f0c2e4-1b3d-4f5a-8c7e-9d0b2c4e6f01", "first");
+    let second = change("b7a1d3f5-2c4e-4a6b-9d8f-0e1c3d5f7a02", "second");
+
+    assert_eq!(server.push(BOARD, &first), (200, json!({ "cursor": 1 })));
+    assert_eq!(server.push(BOARD, &second), (200, json!({ "cursor": 2 })));
+
+    let (status, all) = server.pull(BOARD, 0);
+    assert_eq!(status, 200);
+    assert_eq!(all["cursor"], 2);
+    assert_eq!(all["changes"][0]["cursor"], 1);
+    assert_eq!(all["changes"][0]["db"], first["db"]);
+    assert_eq!(all["changes"][1]["client"], CLIENT);
+
+    let (status, rest) = server.pull(BOARD, 1);
+    assert_eq!(status, 200);
+    assert_eq!(rest["cursor"], 2);
+    assert_eq!(pulled_ids(&rest), [second["id"].as_str().unwrap()]);
+
+    let (_, none) = server.pull(BOARD, 2);
+    assert_eq!(none, json!({ "cursor": 2, "changes": [] }));
+
+    // boards are separate
+    let (_, other) = server.pull("1a7b4d2f-9e3c-4f8b-8d6a-2c4e6f8a0b42", 0);
+    assert_eq!(other, json!({ "cursor": 0, "changes": [] }));
+}
+
+#[test]
+fn a_change_pushed_again_is_stored_once() {
+    let server = Server::start();
+    let first = change("c8b2e4a6-3d5f-4b7c-8e9a-1f2d4e6a8b03", "first");
+    let second = change("d9c3f5b7-4e6a-4c8d-9f0b-2a3e5f7b9c04", "second");
+
+    assert_eq!(server.push(BOARD, &first), (200, json!({ "cursor": 1 })));
+    assert_eq!(server.push(BOARD, &second), (200, json!({ "cursor": 2 })));
+    // e.g. after the response to the first push was lost
+    assert_eq!(server.push(BOARD, &first), (200, json!({ "cursor": 1 })));
+
+    let (_, all) = server.pull(BOARD, 0);
+    assert_eq!(all["cursor"], 2);
+    assert_eq!(
+        pulled_ids(&all),
+        [
+            first["id"].as_str().unwrap(),
+            second["id"].as_str().unwrap()
+        ]
+    );
+}
+
+#[test]
+fn requests_without_the_token_are_rejected() {
+    let server = Server::start();
+    let path = format!("/boards/{}/changes", BOARD);
+    let first = change("e0d4a6c8-5f7b-4d9e-8a1c-3b4f6a8c0d05", "first");
+
+    for token in [None, Some("wrong")] {
+        let (status, body) = server.request("POST", &path, token, Some(&first));
+        assert_eq!(status, 401);
+        assert_eq!(body, json!({ "error": "unauthorized" }));
+
+        let (status, _) = server.request("GET", &path, token, None);
+        assert_eq!(status, 401);
+    }
+
+    // nothing was stored
+    let (_, all) = server.pull(BOARD, 0);
+    assert_eq!(all["cursor"], 0);
+}
+
+#[test]
+fn an_oversized_body_is_rejected() {
+    let server = Server::start();
+    let raw = format!(
+        "POST /boards/{}/changes HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n\
+         Authorization: Bearer {}\r\nContent-Length: {}\r\n\r\n{{}}",
+        BOARD,
+        server.addr,
+        TOKEN,
+        64 * 1024 * 1024
+    );
+
+    let (status, body) = server.send(&raw);
+    assert_eq!(status, 413);
+    assert_eq!(body, json!({ "error": "request body too large" }));
+}
@@ -1,77 +1,975 @@
 use crate::prelude::*;
+use gloo::file::callbacks::FileReader;
+use gloo::timers::callback::{Interval, Timeout};
 use std::rc::Rc;
+use uuid::Uuid;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::JsCast;
+use web_sys::{File, HtmlInputElement, HtmlTextAreaElement};
 use yew::prelude::*;
+use yewdux::prelude::*;
+
+const TOAST_TIMEOUT_MS: u32 = 5000;
+const SYNC_POLL_INTERVAL_MS: u32 = 30_000;
+const SYNC_RETRY_MS: u32 = 2000;
+const SYNC_RETRY_MAX_MS: u32 = 60_000;
+/// Delay before the undo history is saved, so that a burst of changes writes
+/// it once.
+const HISTORY_SAVE_DELAY_MS: u32 = 1000;
 
 pub struct App {
+    boards: BoardCollection,
+    board: Uuid,
     db: DataBase,
+    log: EventLog,
+    history: History,
+    /// Pending save of `history`.
+    history_save: Option<Timeout>,
+    archive: BurnArchive,
+    trash: Trash,
+    show_history: bool,
+    show_trash: bool,
+    /// The message, whether the change it reports can be undone and the timer
+    /// hiding it.
+    toast: Option<(String, bool, Timeout)>,
+    sync: SyncState,
+    /// Whether a push or pull is in flight.
+    syncing: bool,
+    sync_failures: u32,
+    sync_retry: Option<Timeout>,
+    _sync_poll: Interval,
+    /// The file being read for an import.
+    reading: Option<FileReader>,
+    import: Option<Rc<ImportPreview>>,
+
+    on_key_down: Option<Closure<dyn Fn(KeyboardEvent)>>,
+    on_hash_change: Option<Closure<dyn Fn(Event)>>,
 }
 
 pub enum AppMsg {
     TaskEvent(TaskEvent),
+    /// Events applied as one change, undone together.
+    TaskEvents(Vec<TaskEvent>),
     Burn,
+    /// Burns a single card, dropped onto the burn button.
+    BurnCard(Uuid),
     Swap,
+    /// A key pressed outside of text inputs and not handled by a card.
+    Shortcut(KeyboardEvent),
+    Undo,
+    Redo,
+    SaveHistory,
+    HideToast,
+    ToggleHistory,
+    ToggleTrash,
+    /// Puts a burned task back into the rank.
+    RestoreBurned(Uuid, TaskRank),
+    RestoreDeleted(Uuid),
+    EmptyTrash,
+    ConfigureTrashRetention,
+    SwitchBoard(Uuid),
+    LocationChanged,
+    CreateBoard,
+    RenameBoard,
+    DuplicateBoard,
+    DeleteBoard,
+    StorageChanged(String),
+    StorageFailed(String),
+    ConfigureSync,
+    Sync,
+    SyncPushed(Uuid, Uuid, Result<u64, String>),
+    SyncPulled(Uuid, Result<SyncPullResponse, String>),
+    Export(BoardFormat),
+    ReadImport(File),
+    ImportRead(String, Result<String, String>),
+    Import(ImportMode),
+    CloseImport,
 }
 
 impl<'a> Component for App {
     type Message = AppMsg;
     type Properties = ();
 
-    fn create(_ctx: &Context<Self>) -> Self {
+    fn create(ctx: &Context<Self>) -> Self {
+        let boards = BoardCollection::load_from_local_storage();
+        let board = board_from_location()
+            .filter(|&id| boards.get(id).is_some())
+            .unwrap_or(boards.first().id);
+        set_location_board(board);
+
+        let (log, db) = EventLog::load_from_local_storage(board);
+        let link = ctx.link().clone();
+        let sync_poll = Interval::new(SYNC_POLL_INTERVAL_MS, move || {
+            link.send_message(AppMsg::Sync)
+        });
+        ctx.link().send_message(AppMsg::Sync);
         App {
-            db: DataBase::load_from_local_storage(),
+            boards,
+            board,
+            db,
+            log,
+            history: History::load_from_local_storage(board),
+            history_save: None,
+            archive: BurnArchive::load_from_local_storage(board),
+            trash: Trash::load_from_local_storage(board, js_sys::Date::now()),
+            show_history: false,
+            show_trash: false,
+            toast: None,
+            sync: SyncState::load_from_local_storage(board),
+            syncing: false,
+            sync_failures: 0,
+            sync_retry: None,
+            _sync_poll: sync_poll,
+            reading: None,
+            import: None,
+            on_key_down: None,
+            on_hash_change: None,
         }
     }
 
-    fn update(&mut self, _ctx: &Context<Self>, msg: Self::Message) -> bool {
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
         use AppMsg::*;
-        let res = match msg {
-            TaskEvent(e) => self.db.apply_event(&e),
-            Burn => {
-                self.db.burn_tasks(TaskRank::Primary);
-                true
+        // a card deleted by burning it goes to the burn history, not the trash
+        let burning_card = matches!(msg, BurnCard(_));
+        let events = match msg {
+            TaskEvent(e) => vec![e],
+            TaskEvents(events) => events,
+            Burn => vec![burner_list_core::TaskEvent::Burn(TaskBurnData {
+                rank: TaskRank::Primary,
+                carry_over: self.ask_carry_over(TaskRank::Primary),
+            })],
+            BurnCard(id) => vec![burner_list_core::TaskEvent::Delete(TaskDeleteData { id })],
+            Swap => vec![burner_list_core::TaskEvent::Swap(TaskSwapData {
+                rank1: TaskRank::Primary,
+                rank2: TaskRank::Secondary,
+            })],
+            Shortcut(e) => match self.shortcut(&e) {
+                Some(event) => vec![event],
+                None => return false,
+            },
+            Undo => {
+                self.toast = None;
+                // step from what other tabs have done, not from a stale board
+                let synced = self.catch_up();
+                if !self.history.undo(&mut self.db, js_sys::Date::now()) {
+                    return synced;
+                }
+                self.forget_restored();
+                self.save_history_later(ctx);
+                self.log.compact(&self.db);
+                self.start_sync(ctx);
+                return true;
             }
-            Swap => {
-                self.db.swap_tasks(TaskRank::Primary, TaskRank::Secondary);
-                true
+            Redo => {
+                self.toast = None;
+                // step from what other tabs have done, not from a stale board
+                let synced = self.catch_up();
+                if !self.history.redo(&mut self.db, js_sys::Date::now()) {
+                    return synced;
+                }
+                self.forget_restored();
+                self.save_history_later(ctx);
+                self.log.compact(&self.db);
+                self.start_sync(ctx);
+                return true;
+            }
+            SaveHistory => {
+                self.save_history();
+                return false;
+            }
+            HideToast => {
+                self.toast = None;
+                return true;
+            }
+            ToggleHistory => {
+                self.show_history = !self.show_history;
+                self.show_trash = false;
+                return true;
+            }
+            ToggleTrash => {
+                self.show_trash = !self.show_trash;
+                self.show_history = false;
+                return true;
+            }
+            RestoreBurned(id, rank) => {
+                let task = match self
+                    .archive
+                    .burned_tasks()
+                    .find(|(_, task)| task.id() == id)
+                {
+                    Some((_, task)) => task.clone(),
+                    None => return false,
+                };
+                self.restore(ctx, &task, rank);
+                return true;
+            }
+            RestoreDeleted(id) => {
+                let task = match self.trash.take(id) {
+                    Some(task) => task,
+                    None => return false,
+                };
+                self.trash.save_to_local_storage();
+                self.restore(ctx, &task, task.rank);
+                return true;
+            }
+            EmptyTrash => {
+                if !gloo::dialogs::confirm("ゴミ箱を空にしますか？この操作は元に戻せません。")
+                {
+                    return false;
+                }
+                self.trash.clear();
+                self.trash.save_to_local_storage();
+                return true;
+            }
+            ConfigureTrashRetention => {
+                self.configure_trash_retention();
+                return true;
+            }
+            SwitchBoard(id) => {
+                return self.open_board(ctx, id);
+            }
+            LocationChanged => {
+                let id = board_from_location().unwrap_or(self.board);
+                return self.open_board(ctx, id);
+            }
+            CreateBoard => {
+                if let Some(name) = prompt_board_name("新しいボードの名前", "") {
+                    let id = self.boards.create(&name);
+                    self.boards.save_to_local_storage();
+                    self.open_board(ctx, id);
+                }
+                return true;
+            }
+            RenameBoard => {
+                let name = self.current_board_name();
+                if let Some(name) = prompt_board_name("ボードの名前", &name) {
+                    self.boards.rename(self.board, &name);
+                    self.boards.save_to_local_storage();
+                }
+                return true;
+            }
+            DuplicateBoard => {
+                let name = format!("{} のコピー", self.current_board_name());
+                if let Some(name) = prompt_board_name("複製したボードの名前", &name) {
+                    if let Some(id) = self.boards.duplicate(self.board, &name) {
+                        self.boards.save_to_local_storage();
+                        self.open_board(ctx, id);
+                    }
+                }
+                return true;
+            }
+            DeleteBoard => {
+                let message = format!(
+                    "ボード「{}」を削除しますか？この操作は元に戻せません。",
+                    self.current_board_name()
+                );
+                if gloo::dialogs::confirm(&message) && self.boards.delete(self.board) {
+                    self.boards.save_to_local_storage();
+                    self.open_board(ctx, self.boards.first().id);
+                }
+                return true;
+            }
+            StorageChanged(key) => {
+                return self.storage_changed(ctx, &key);
+            }
+            StorageFailed(e) => {
+                self.show_error(ctx, &format!("保存できませんでした: {}", e));
+                return true;
+            }
+            ConfigureSync => {
+                self.configure_sync(ctx);
+                return true;
+            }
+            Sync => {
+                self.start_sync(ctx);
+                return true;
+            }
+            SyncPushed(board, id, result) => {
+                if board != self.board {
+                    return false;
+                }
+                self.syncing = false;
+                match result {
+                    Ok(cursor) => {
+                        log::debug!("[sync] pushed {} at cursor {}", id, cursor);
+                        self.sync.pushed(id);
+                        self.sync_failures = 0;
+                        self.start_sync(ctx);
+                    }
+                    Err(e) => self.sync_failed(ctx, &e),
+                }
+                return true;
+            }
+            SyncPulled(board, result) => {
+                if board != self.board {
+                    return false;
+                }
+                self.syncing = false;
+                match result {
+                    Ok(response) => {
+                        self.sync_failures = 0;
+                        if let Some(db) = self.log.sync() {
+                            self.db = db;
+                        }
+                        self.sync.enqueue(&self.db);
+                        if self.sync.pulled(response, &mut self.db) {
+                            self.log.compact(&self.db);
+                        }
+                    }
+                    Err(e) => self.sync_failed(ctx, &e),
+                }
+                return true;
+            }
+            Export(format) => {
+                let file_name = format!("{}.{}", self.current_board_name(), format.extension());
+                download(
+                    &file_name,
+                    format.mime_type(),
+                    &format.export(&self.db, &self.archive),
+                );
+                return false;
+            }
+            ReadImport(file) => {
+                let file = gloo::file::File::from(file);
+                let name = file.name();
+                let link = ctx.link().clone();
+                self.reading = Some(gloo::file::callbacks::read_as_text(&file, move |text| {
+                    link.send_message(AppMsg::ImportRead(name, text.map_err(|e| e.to_string())))
+                }));
+                return false;
+            }
+            ImportRead(name, text) => {
+                self.reading = None;
+                let format = BoardFormat::from_file_name(&name);
+                let preview = text
+                    .and_then(|text| format.import(&text, js_sys::Date::now()))
+                    .map(|imported| ImportPreview::new(&self.db, &name, imported));
+                match preview {
+                    Ok(preview) => self.import = Some(Rc::new(preview)),
+                    Err(e) => gloo::dialogs::alert(&format!(
+                        "「{}」を読み込めませんでした。\n{}",
+                        name, e
+                    )),
+                }
+                return true;
+            }
+            Import(mode) => {
+                let preview = match self.import.take() {
+                    Some(preview) => preview,
+                    None => return false,
+                };
+                if let Some(db) = self.log.sync() {
+                    self.db = db;
+                }
+                let prev = self.db.clone();
+                preview.apply(&mut self.db, mode, js_sys::Date::now());
+                self.history.record(prev);
+                self.save_history_later(ctx);
+                self.log.compact(&self.db);
+                self.start_sync(ctx);
+                self.show_toast(ctx, "インポートしました");
+                return true;
+            }
+            CloseImport => {
+                self.import = None;
+                return true;
             }
         };
 
-        self.db.save_to_local_storage();
-        res
+        // apply on top of what other tabs have done
+        if let Some(db) = self.log.sync() {
+            self.db = db;
+        }
+
+        let now = js_sys::Date::now();
+        let prev = self.db.clone();
+        let mut added = 0;
+        let mut changed = false;
+        for event in events {
+            log::debug!("[app] {:?}", event);
+            let burned = match event {
+                burner_list_core::TaskEvent::Burn(TaskBurnData { rank, carry_over }) => self
+                    .db
+                    .get_burnable(rank, carry_over)
+                    .into_iter()
+                    .cloned()
+                    .collect(),
+                burner_list_core::TaskEvent::Delete(TaskDeleteData { id }) => {
+                    self.db.get_by_id(id).cloned().into_iter().collect()
+                }
+                _ => vec![],
+            };
+            if !self.db.apply_event(&event, now) {
+                continue;
+            }
+
+            match &event {
+                burner_list_core::TaskEvent::Add(_) => added += 1,
+                burner_list_core::TaskEvent::Delete(_) if burning_card => {
+                    self.archive.push(burned, now);
+                    self.archive.save_to_local_storage();
+                    self.show_toast(ctx, "カードをバーンしました");
+                }
+                burner_list_core::TaskEvent::Delete(_) => {
+                    for task in burned {
+                        self.trash.push(task, now);
+                    }
+                    self.trash.save_to_local_storage();
+                    self.show_toast(ctx, "カードを削除しました");
+                }
+                burner_list_core::TaskEvent::Burn(_) => {
+                    self.archive.push(burned, now);
+                    self.archive.save_to_local_storage();
+                    self.show_toast(ctx, "バーンしました");
+                }
+                _ => {}
+            }
+            self.log.append(event, now, &self.db);
+            changed = true;
+        }
+        if !changed {
+            return false;
+        }
+        if added > 1 {
+            self.show_toast(ctx, &format!("{}件のタスクを追加しました", added));
+        }
+
+        self.history.record(prev);
+        self.save_history_later(ctx);
+        self.start_sync(ctx);
+        true
+    }
+
+    fn rendered(&mut self, ctx: &Context<Self>, first_render: bool) {
+        if !first_render {
+            return;
+        }
+
+        let on_undo = ctx.link().callback(move |_| AppMsg::Undo);
+        let on_redo = ctx.link().callback(move |_| AppMsg::Redo);
+        let on_shortcut = ctx.link().callback(AppMsg::Shortcut);
+        let on_key_down = Closure::<dyn Fn(KeyboardEvent)>::wrap(Box::new(move |e| {
+            if is_text_input(&e) || e.default_prevented() {
+                return;
+            }
+            if !(e.ctrl_key() || e.meta_key()) {
+                on_shortcut.emit(e);
+                return;
+            }
+            if e.key().eq_ignore_ascii_case("z") {
+                e.prevent_default();
+                if e.shift_key() {
+                    on_redo.emit(());
+                } else {
+                    on_undo.emit(());
+                }
+            }
+        }));
+
+        let on_location_changed = ctx.link().callback(move |_| AppMsg::LocationChanged);
+        let on_hash_change =
+            Closure::<dyn Fn(Event)>::wrap(Box::new(move |e| on_location_changed.emit(e)));
+
+        let on_storage_changed = ctx.link().callback(AppMsg::StorageChanged);
+        storage().watch(Some(Box::new(move |key| on_storage_changed.emit(key))));
+        let on_storage_failed = ctx.link().callback(AppMsg::StorageFailed);
+        storage().on_error(Some(Box::new(move |e| on_storage_failed.emit(e))));
+
+        let window = web_sys::window().expect("window");
+        let document = window.document().expect("document");
+        document
+            .add_event_listener_with_callback("keydown", on_key_down.as_ref().unchecked_ref())
+            .unwrap();
+        window
+            .add_event_listener_with_callback("hashchange", on_hash_change.as_ref().unchecked_ref())
+            .unwrap();
+
+        self.on_key_down = Some(on_key_down);
+        self.on_hash_change = Some(on_hash_change);
+    }
+
+    fn destroy(&mut self, _ctx: &Context<Self>) {
+        let window = web_sys::window().expect("window");
+        let document = window.document().expect("document");
+        if let Some(on_key_down) = self.on_key_down.take() {
+            document
+                .remove_event_listener_with_callback(
+                    "keydown",
+                    on_key_down.as_ref().unchecked_ref(),
+                )
+                .unwrap();
+        }
+        if let Some(on_hash_change) = self.on_hash_change.take() {
+            window
+                .remove_event_listener_with_callback(
+                    "hashchange",
+                    on_hash_change.as_ref().unchecked_ref(),
+                )
+                .unwrap();
+        }
+        storage().watch(None);
+        storage().on_error(None);
+        self.save_history();
     }
 
     fn view(&self, ctx: &Context<Self>) -> Html {
         let on_edit = ctx.link().callback(move |e| AppMsg::TaskEvent(e));
+        let on_batch = ctx.link().callback(AppMsg::TaskEvents);
         let on_burn = ctx.link().callback(move |_| AppMsg::Burn);
         let on_swap = ctx.link().callback(move |_| AppMsg::Swap);
+        let on_undo = ctx.link().callback(move |_| AppMsg::Undo);
+        let on_burn_card = ctx.link().callback(AppMsg::BurnCard);
+        let on_toggle_history = ctx.link().callback(move |_| AppMsg::ToggleHistory);
+        let on_toggle_trash = ctx.link().callback(move |_| AppMsg::ToggleTrash);
+        let on_restore_burned = ctx
+            .link()
+            .callback(move |(id, rank)| AppMsg::RestoreBurned(id, rank));
+        let on_restore_deleted = ctx.link().callback(AppMsg::RestoreDeleted);
+        let on_empty_trash = ctx.link().callback(move |_| AppMsg::EmptyTrash);
+        let on_configure_trash_retention = ctx
+            .link()
+            .callback(move |_| AppMsg::ConfigureTrashRetention);
+        let on_switch_board = ctx.link().callback(AppMsg::SwitchBoard);
+        let on_create_board = ctx.link().callback(move |_| AppMsg::CreateBoard);
+        let on_rename_board = ctx.link().callback(move |_| AppMsg::RenameBoard);
+        let on_duplicate_board = ctx.link().callback(move |_| AppMsg::DuplicateBoard);
+        let on_delete_board = ctx.link().callback(move |_| AppMsg::DeleteBoard);
+        let on_configure_sync = ctx.link().callback(move |_| AppMsg::ConfigureSync);
+        let on_export = ctx.link().callback(AppMsg::Export);
+        let on_import_file = ctx.link().callback(AppMsg::ReadImport);
+        let on_import = ctx.link().callback(AppMsg::Import);
+        let on_close_import = ctx.link().callback(move |_| AppMsg::CloseImport);
+        let on_file_drag_over = Callback::from(move |e: DragEvent| {
+            if dragged_files(&e) {
+                e.prevent_default();
+            }
+        });
+        let on_file_drop = {
+            let on_import_file = on_import_file.clone();
+            Callback::from(move |e: DragEvent| {
+                if !dragged_files(&e) {
+                    return;
+                }
+                e.prevent_default();
+                let file = e
+                    .data_transfer()
+                    .and_then(|transfer| transfer.files())
+                    .and_then(|files| files.get(0));
+                if let Some(file) = file {
+                    on_import_file.emit(file);
+                }
+            })
+        };
         html! {
             <ContextProvider<Rc<DataBase>> context={Rc::new(self.db.clone())}>
-                <main>
+                <main ondragover={on_file_drag_over} ondrop={on_file_drop}>
                     // <StoreWatcher/>
-                    <DraggableArea/>
+                    <DraggableArea onedit={on_edit.clone()} onburn={on_burn_card}/>
                     <div class="split">
                         <div class="split-item" style="margin-right: -10px">
-                            <TaskList rank={TaskRank::Primary} onedit={on_edit.clone()}/>
+                            <TaskList rank={TaskRank::Primary}
+                                onedit={on_edit.clone()}
+                                onbatch={on_batch.clone()}
+                            />
                         </div>
 
                         <div class="split-item">
                             <div class="split-right">
                                 <div class="split-right-item" style="margin-bottom: -10px">
-                                    <TaskList rank={TaskRank::Secondary} onedit={on_edit.clone()}/>
+                                    <TaskList rank={TaskRank::Secondary}
+                                        onedit={on_edit.clone()}
+                                        onbatch={on_batch.clone()}
+                                    />
                                 </div>
 
                                 <div class="split-right-item">
-                                    <TaskList rank={TaskRank::Other} onedit={on_edit.clone()}/>
+                                    <TaskList rank={TaskRank::Other}
+                                        onedit={on_edit.clone()}
+                                        onbatch={on_batch.clone()}
+                                    />
                                 </div>
                             </div>
                         </div>
                     </div>
                     <SwapButton onclick={on_swap}/>
                     <BurnButton onclick={on_burn}/>
-                    // <DeleteArea onedit={on_edit.clone()}/>
+                    <HistoryButton onclick={on_toggle_history.clone()}/>
+                    <TrashButton onclick={on_toggle_trash.clone()}/>
+                    <BoardSwitcher boards={Rc::new(self.boards.clone())}
+                        current={self.board}
+                        onswitch={on_switch_board}
+                        oncreate={on_create_board}
+                        onrename={on_rename_board}
+                        onduplicate={on_duplicate_board}
+                        ondelete={on_delete_board}
+                        onsync={on_configure_sync}
+                        onexport={on_export}
+                        onimport={on_import_file}
+                        sync_status={self.sync_status()}
+                    />
+                    if self.show_history {
+                        <BurnHistory archive={Rc::new(self.archive.clone())}
+                            onrestore={on_restore_burned}
+                            onclose={on_toggle_history}
+                        />
+                    }
+                    if self.show_trash {
+                        <TrashBin trash={Rc::new(self.trash.clone())}
+                            onrestore={on_restore_deleted}
+                            onempty={on_empty_trash}
+                            onretention={on_configure_trash_retention}
+                            onclose={on_toggle_trash}
+                        />
+                    }
+                    if let Some(preview) = &self.import {
+                        <ImportDialog preview={preview.clone()}
+                            onimport={on_import}
+                            onclose={on_close_import}
+                        />
+                    }
+                    if let Some((message, undoable, _)) = &self.toast {
+                        <UndoToast message={message.clone()}
+                            onundo={undoable.then_some(on_undo)}
+                        />
+                    }
+                    <DeleteArea/>
                     // <DraggingCard/>
                 </main>
             </ContextProvider<Rc<DataBase>>>
         }
     }
 }
+
+impl App {
+    /// Loads everything stored for `board` and points the location at it.
+    fn open_board(&mut self, ctx: &Context<Self>, board: Uuid) -> bool {
+        let board = if self.boards.get(board).is_some() {
+            board
+        } else {
+            self.boards.first().id
+        };
+        set_location_board(board);
+        if board == self.board {
+            return false;
+        }
+
+        self.save_history();
+        let (log, db) = EventLog::load_from_local_storage(board);
+        self.board = board;
+        self.db = db;
+        self.log = log;
+        self.history = History::load_from_local_storage(board);
+        self.archive = BurnArchive::load_from_local_storage(board);
+        self.trash = Trash::load_from_local_storage(board, js_sys::Date::now());
+        self.toast = None;
+        self.sync = SyncState::load_from_local_storage(board);
+        self.syncing = false;
+        self.sync_failures = 0;
+        self.sync_retry = None;
+        self.start_sync(ctx);
+        true
+    }
+
+    /// Saves the undo history after `HISTORY_SAVE_DELAY_MS`, unless another
+    /// change comes in before.
+    fn save_history_later(&mut self, ctx: &Context<Self>) {
+        let link = ctx.link().clone();
+        self.history_save = Some(Timeout::new(HISTORY_SAVE_DELAY_MS, move || {
+            link.send_message(AppMsg::SaveHistory)
+        }));
+    }
+
+    /// Saves the undo history now if a save is pending.
+    fn save_history(&mut self) {
+        if self.history_save.take().is_some() {
+            self.history.save_to_local_storage();
+        }
+    }
+
+    /// Puts back a deleted or burned task into `rank` as one undoable change.
+    fn restore(&mut self, ctx: &Context<Self>, task: &TaskData, rank: TaskRank) {
+        self.catch_up();
+        let prev = self.db.clone();
+        // back in its place, also on the devices that saw it deleted
+        if !self.db.restore_task(task, rank, js_sys::Date::now()) {
+            return;
+        }
+        self.forget_restored();
+        self.history.record(prev);
+        self.save_history_later(ctx);
+        self.log.compact(&self.db);
+        self.start_sync(ctx);
+    }
+
+    /// Forgets the deleted and burned tasks that are back on the board, e.g.
+    /// after an undo, so that they cannot be restored twice.
+    fn forget_restored(&mut self) {
+        if self.trash.remove_restored(&self.db) {
+            self.trash.save_to_local_storage();
+        }
+        if self.archive.remove_restored(&self.db) {
+            self.archive.save_to_local_storage();
+        }
+    }
+
+    /// Applies the entries other tabs have appended to the event log. Returns
+    /// whether the board changed.
+    fn catch_up(&mut self) -> bool {
+        match self.log.sync() {
+            Some(db) => {
+                self.db = db;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Picks up a change another tab made to the local storage.
+    fn storage_changed(&mut self, ctx: &Context<Self>, key: &str) -> bool {
+        if key == "boards" {
+            self.boards = BoardCollection::load_from_local_storage();
+            self.open_board(ctx, self.board);
+            return true;
+        }
+
+        if key == board_key(self.board, "events") {
+            if let Some(db) = self.log.sync() {
+                self.db = db;
+                return true;
+            }
+        } else if key == board_key(self.board, "history") {
+            self.history = History::load_from_local_storage(self.board);
+        } else if key == board_key(self.board, "archive") {
+            self.archive = BurnArchive::load_from_local_storage(self.board);
+            return self.show_history;
+        } else if key == board_key(self.board, "trash") {
+            self.trash = Trash::load_from_local_storage(self.board, js_sys::Date::now());
+            return self.show_trash;
+        } else if key == board_key(self.board, "sync") {
+            self.sync = SyncState::load_from_local_storage(self.board);
+            return true;
+        }
+
+        false
+    }
+
+    /// Queues the local changes, pushes them one at a time and then pulls the
+    /// changes of the other devices.
+    fn start_sync(&mut self, ctx: &Context<Self>) {
+        let config = match self.sync.config() {
+            Some(config) => config.clone(),
+            None => return,
+        };
+
+        self.sync.enqueue(&self.db);
+        if self.syncing {
+            return;
+        }
+
+        self.sync_retry = None;
+        self.syncing = true;
+        let board = self.board;
+        if let Some(change) = self.sync.next_push().cloned() {
+            ctx.link().send_future(async move {
+                let result = config.push(&change).await;
+                AppMsg::SyncPushed(board, change.id, result)
+            });
+        } else {
+            let cursor = self.sync.cursor();
+            ctx.link().send_future(async move {
+                let result = config.pull(cursor).await;
+                AppMsg::SyncPulled(board, result)
+            });
+        }
+    }
+
+    /// Retries with an exponential backoff. The queued changes stay in the
+    /// local storage in the meantime.
+    fn sync_failed(&mut self, ctx: &Context<Self>, error: &str) {
+        log::warn!("[sync] {}", error);
+        self.sync_failures += 1;
+        let delay = (SYNC_RETRY_MS << self.sync_failures.min(5)).min(SYNC_RETRY_MAX_MS);
+        let link = ctx.link().clone();
+        self.sync_retry = Some(Timeout::new(delay, move || link.send_message(AppMsg::Sync)));
+    }
+
+    fn configure_sync(&mut self, ctx: &Context<Self>) {
+        let current = self.sync.config().cloned();
+        let endpoint = match gloo::dialogs::prompt(
+            "同期サーバーの URL (空欄で同期を解除)",
+            current.as_ref().map(|config| config.endpoint.as_str()),
+        ) {
+            Some(endpoint) => endpoint.trim().to_owned(),
+            None => return,
+        };
+        if endpoint.is_empty() {
+            self.sync.configure(None);
+            return;
+        }
+
+        let token = match gloo::dialogs::prompt(
+            "アクセストークン",
+            current.as_ref().map(|config| config.token.as_str()),
+        ) {
+            Some(token) => token.trim().to_owned(),
+            None => return,
+        };
+
+        let remote = current
+            .as_ref()
+            .map(|config| config.remote)
+            .unwrap_or(self.board)
+            .to_string();
+        let remote = match gloo::dialogs::prompt(
+            "同期するボードの ID (他の端末と同じ ID にしてください)",
+            Some(&remote),
+        ) {
+            Some(remote) => remote,
+            None => return,
+        };
+        let remote = match Uuid::parse_str(remote.trim()) {
+            Ok(remote) => remote,
+            Err(_) => {
+                gloo::dialogs::alert("ボードの ID が正しくありません");
+                return;
+            }
+        };
+
+        self.sync.configure(Some(SyncConfig {
+            endpoint,
+            token,
+            remote,
+        }));
+        self.sync_failures = 0;
+        self.start_sync(ctx);
+    }
+
+    fn configure_trash_retention(&mut self) {
+        let days = match gloo::dialogs::prompt(
+            "削除したタスクをゴミ箱に保存する日数",
+            Some(&self.trash.retention_days().to_string()),
+        ) {
+            Some(days) => days,
+            None => return,
+        };
+        match days.trim().parse::<u32>() {
+            Ok(days) if days > 0 => {
+                self.trash.set_retention_days(days, js_sys::Date::now());
+                self.trash.save_to_local_storage();
+            }
+            _ => gloo::dialogs::alert("1 以上の日数を入力してください"),
+        }
+    }
+
+    fn sync_status(&self) -> Option<String> {
+        self.sync.config()?;
+        let status = if self.syncing {
+            "同期中…".to_owned()
+        } else if self.sync_failures > 0 {
+            format!("オフライン ({}件未送信)", self.sync.pending())
+        } else {
+            "同期済み".to_owned()
+        };
+        Some(status)
+    }
+
+    fn current_board_name(&self) -> String {
+        self.boards
+            .get(self.board)
+            .map(|board| board.name.clone())
+            .unwrap_or_default()
+    }
+
+    /// Handles the keys that work anywhere on the board: `n` or `a` adds a
+    /// card to the rank of the focused card, `b` burns, `s` swaps and an arrow
+    /// key focuses the first card when none has the focus.
+    fn shortcut(&self, e: &KeyboardEvent) -> Option<burner_list_core::TaskEvent> {
+        if e.alt_key() || e.repeat() {
+            return None;
+        }
+        let focused = Dispatch::<FocusedCardId>::new().get().id;
+        match e.key().as_str() {
+            "n" | "a" => {
+                e.prevent_default();
+                let rank = focused
+                    .and_then(|id| self.db.get_by_id(id))
+                    .map(|task| task.rank)
+                    .unwrap_or(TaskRank::Primary);
+                Dispatch::<AddingRank>::new().set(AddingRank { rank: Some(rank) });
+                None
+            }
+            "b" => {
+                e.prevent_default();
+                Some(burner_list_core::TaskEvent::Burn(TaskBurnData {
+                    rank: TaskRank::Primary,
+                    carry_over: self.ask_carry_over(TaskRank::Primary),
+                }))
+            }
+            "s" => {
+                e.prevent_default();
+                Some(burner_list_core::TaskEvent::Swap(TaskSwapData {
+                    rank1: TaskRank::Primary,
+                    rank2: TaskRank::Secondary,
+                }))
+            }
+            key if Arrow::from_key(key).is_some() && focused.is_none() => {
+                e.prevent_default();
+                let first = first_card(&self.db);
+                Dispatch::<FocusedCardId>::new().set(FocusedCardId { id: first });
+                None
+            }
+            _ => None,
+        }
+    }
+
+    /// Asks whether to keep the unfinished tasks when `rank` has both finished
+    /// and unfinished ones.
+    fn ask_carry_over(&self, rank: TaskRank) -> bool {
+        let tasks = self.db.get_by_rank(rank);
+        let done = tasks.iter().filter(|task| task.done).count();
+        if done == 0 || done == tasks.len() {
+            return false;
+        }
+
+        gloo::dialogs::confirm("未完了のタスクを次のページに持ち越しますか？")
+    }
+
+    fn show_toast(&mut self, ctx: &Context<Self>, message: &str) {
+        self.set_toast(ctx, message, true);
+    }
+
+    /// Shows `message` without the undo button.
+    fn show_error(&mut self, ctx: &Context<Self>, message: &str) {
+        self.set_toast(ctx, message, false);
+    }
+
+    fn set_toast(&mut self, ctx: &Context<Self>, message: &str, undoable: bool) {
+        let link = ctx.link().clone();
+        let timeout = Timeout::new(TOAST_TIMEOUT_MS, move || {
+            link.send_message(AppMsg::HideToast)
+        });
+        self.toast = Some((message.to_owned(), undoable, timeout));
+    }
+}
+
+fn is_text_input(e: &KeyboardEvent) -> bool {
+    e.target()
+        .map(|target| {
+            target.dyn_ref::<HtmlTextAreaElement>().is_some()
+                || target.dyn_ref::<HtmlInputElement>().is_some()
+        })
+        .unwrap_or(false)
+}
+
+/// Whether files, rather than text or a card, are dragged over the page.
+fn dragged_files(e: &DragEvent) -> bool {
+    e.data_transfer()
+        .map(|transfer| transfer.types().includes(&"Files".into(), 0))
+        .unwrap_or(false)
+}
+
+fn prompt_board_name(message: &str, default: &str) -> Option<String> {
+    gloo::dialogs::prompt(message, Some(default))
+        .map(|name| name.trim().to_owned())
+        .filter(|name| !name.is_empty())
+}
@@ -0,0 +1,113 @@
+use crate::prelude::*;
+use std::rc::Rc;
+use uuid::Uuid;
+use wasm_bindgen::JsCast;
+use web_sys::{File, HtmlInputElement, HtmlSelectElement};
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct BoardSwitcherProps {
+    pub boards: Rc<BoardCollection>,
+    pub current: Uuid,
+    pub onswitch: Callback<Uuid>,
+    pub oncreate: Callback<MouseEvent>,
+    pub onrename: Callback<MouseEvent>,
+    pub onduplicate: Callback<MouseEvent>,
+    pub ondelete: Callback<MouseEvent>,
+    pub onsync: Callback<MouseEvent>,
+    pub onexport: Callback<BoardFormat>,
+    pub onimport: Callback<File>,
+    pub sync_status: Option<String>,
+}
+
+#[function_component(BoardSwitcher)]
+pub fn board_switcher(props: &BoardSwitcherProps) -> Html {
+    let on_change = {
+        let onswitch = props.onswitch.clone();
+        Callback::from(move |e: Event| {
+            let id = e
+                .target()
+                .and_then(|target| target.dyn_into::<HtmlSelectElement>().ok())
+                .and_then(|select| Uuid::parse_str(&select.value()).ok());
+            if let Some(id) = id {
+                onswitch.emit(id);
+            }
+        })
+    };
+
+    let on_export = {
+        let onexport = props.onexport.clone();
+        Callback::from(move |e: Event| {
+            let select = match e
+                .target()
+                .and_then(|target| target.dyn_into::<HtmlSelectElement>().ok())
+            {
+                Some(select) => select,
+                None => return,
+            };
+            let format = BoardFormat::ALL
+                .into_iter()
+                .find(|format| format.extension() == select.value());
+            if let Some(format) = format {
+                onexport.emit(format);
+            }
+            // back to the placeholder, so that it works as a menu
+            select.set_value("");
+        })
+    };
+
+    let on_import = {
+        let onimport = props.onimport.clone();
+        Callback::from(move |e: Event| {
+            let input = match e
+                .target()
+                .and_then(|target| target.dyn_into::<HtmlInputElement>().ok())
+            {
+                Some(input) => input,
+                None => return,
+            };
+            if let Some(file) = input.files().and_then(|files| files.get(0)) {
+                onimport.emit(file);
+            }
+            // lets the same file be picked again
+            input.set_value("");
+        })
+    };
+
+    html! {
+        <div class="board-switcher">
+            <select onchange={on_change}>
+                { for props.boards.boards().iter().map(|board| html!(
+                    <option key={board.id.to_string()}
+                        value={board.id.to_string()}
+                        selected={board.id == props.current}
+                    >
+                        { board.name.clone() }
+                    </option>
+                )) }
+            </select>
+            <button onclick={props.oncreate.clone()}>{"新規"}</button>
+            <button onclick={props.onrename.clone()}>{"名前変更"}</button>
+            <button onclick={props.onduplicate.clone()}>{"複製"}</button>
+            <button onclick={props.ondelete.clone()}
+                disabled={props.boards.boards().len() <= 1}
+            >
+                {"削除"}
+            </button>
+            <select class="board-switcher-export" onchange={on_export}>
+                <option value="" selected=true disabled=true>{"エクスポート"}</option>
+                { for BoardFormat::ALL.iter().map(|format| html!(
+                    <option value={format.extension()}>{ format.label() }</option>
+                )) }
+            </select>
+            <label class="board-switcher-import">
+                {"インポート"}
+                <input type="file" accept=".json,.md,.markdown,.txt,.ics,.csv" onchange={on_import}/>
+            </label>
+            <button onclick={props.onsync.clone()}>{"同期"}</button>
+            if let Some(status) = &props.sync_status {
+                <span class="sync-status">{ status.clone() }</span>
+            }
+        </div>
+    }
+}
@@ -0,0 +1,57 @@
+use crate::prelude::*;
+use std::rc::Rc;
+use uuid::Uuid;
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct BurnHistoryProps {
+    pub archive: Rc<BurnArchive>,
+    /// Puts the burned task back into the rank, under its own id.
+    pub onrestore: Callback<(Uuid, TaskRank)>,
+    pub onclose: Callback<MouseEvent>,
+}
+
+#[function_component(BurnHistory)]
+pub fn burn_history(props: &BurnHistoryProps) -> Html {
+    let pages = props.archive.pages().collect::<Vec<_>>();
+
+    html! {
+        <div class="burn-history">
+            <div class="burn-history-header">
+                <div>{"バーン履歴"}</div>
+                <button class="burn-history-close" onclick={props.onclose.clone()}>{"×"}</button>
+            </div>
+            <div class="burn-history-pages">
+                if pages.is_empty() {
+                    <div class="burn-history-empty">{"バーンしたページはまだありません"}</div>
+                }
+                { for pages.iter().map(|page| html!(
+                    <div class="burn-page" key={page.id.to_string()}>
+                        <div class="burn-page-date">{ format_timestamp(page.burned_at) }</div>
+                        { for page.tasks.iter().map(|task| restorable_task(task, &props.onrestore)) }
+                    </div>
+                )) }
+            </div>
+        </div>
+    }
+}
+
+fn restorable_task(task: &TaskData, onrestore: &Callback<(Uuid, TaskRank)>) -> Html {
+    let id = task.id();
+    let restore_buttons = TaskRank::ALL.into_iter().map(|rank| {
+        let onclick = onrestore.reform(move |_: MouseEvent| (id, rank));
+        html! {
+            <button class="burn-page-restore" {onclick}>{ rank.to_string() }</button>
+        }
+    });
+
+    html! {
+        <div class="burn-page-task" key={task.id().to_string()}>
+            <p class="card">{ task_value_as_html(&task.value, task.done) }</p>
+            <div class="burn-page-restore-buttons">
+                <span>{"戻す:"}</span>
+                { for restore_buttons }
+            </div>
+        </div>
+    }
+}
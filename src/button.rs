@@ -1,5 +1,9 @@
 mod burn_button;
+mod history_button;
 mod swap_button;
+mod trash_button;
 
 pub use burn_button::*;
+pub use history_button::*;
 pub use swap_button::*;
+pub use trash_button::*;
@@ -1,4 +1,6 @@
+use crate::prelude::*;
 use yew::prelude::*;
+use yewdux::prelude::*;
 
 #[derive(Properties, PartialEq)]
 pub struct BurnButtonProps {
@@ -7,8 +9,18 @@ pub struct BurnButtonProps {
 
 #[function_component(BurnButton)]
 pub fn burn_button(props: &BurnButtonProps) -> Html {
+    // a card dropped here is burned alone
+    let target = use_store::<DraggingDropTarget>().0.target;
+    let mut class = classes!("burn-button");
+    if target == Some(DropTarget::Burn) {
+        class.push("drop-hover");
+    }
+
     html! {
-        <button class="burn-button" onclick={props.onclick.clone()}>
+        <button class={class}
+            onclick={props.onclick.clone()}
+            data-drop-target={DropTarget::Burn.name()}
+        >
         <svg xmlns="http://www.w3.org/2000/svg" width="24" height="24" fill="currentColor" class="bi bi-fire" viewBox="0 0 16 16"><path d="M8 16c3.314 0 6-2 6-5.5 0-1.5-.5-4-2.5-6 .25 1.5-1.25 2-1.25 2C11 4 9 .5 6 0c.357 2 .5 4-2 6-1.25 1-2 2.729-2 4.5C2 14 4.686 16 8 16Zm0-1c-1.657 0-3-1-3-2.75 0-.75.25-2 1.25-3C6.125 10 7 10.5 7 10.5c-.375-1.25.5-3.25 2-3.5-.179 1-.25 2 1 3 .625.5 1 1.364 1 2.25C11 14 9.657 15 8 15Z"/></svg>
         </button>
     }
@@ -2,9 +2,12 @@ mod add_card;
 mod blank_card;
 mod card;
 mod drag_card;
+mod keyboard;
 mod util;
 
 pub use add_card::*;
 pub use blank_card::*;
 pub use card::*;
 pub use drag_card::*;
+pub(crate) use keyboard::{first_card, Arrow};
+pub(crate) use util::{format_timestamp, task_value_as_html};
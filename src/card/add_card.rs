@@ -1,11 +1,15 @@
 use crate::prelude::*;
-use web_sys::HtmlTextAreaElement;
+use uuid::Uuid;
+use wasm_bindgen::JsCast;
+use web_sys::{DataTransfer, HtmlTextAreaElement};
 use yew::prelude::*;
+use yewdux::prelude::*;
 
 #[derive(Properties, PartialEq)]
 pub struct AddCardProps {
     pub rank: TaskRank,
     pub onedit: Callback<TaskEvent>,
+    pub onbatch: Callback<Vec<TaskEvent>>,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -18,6 +22,23 @@ pub enum AddCardState {
 pub fn add_card(props: &AddCardProps) -> Html {
     let state = use_state(|| AddCardState::None);
     let node_ref = use_node_ref();
+    let (adding, dispatch_adding) = use_store::<AddingRank>();
+
+    // opened from the keyboard
+    {
+        let state = state.clone();
+        let rank = props.rank;
+        use_effect_with_deps(
+            move |adding| {
+                if *adding == Some(rank) {
+                    state.set(AddCardState::Editting);
+                    dispatch_adding.set(AddingRank { rank: None });
+                }
+                || {}
+            },
+            adding.rank,
+        );
+    }
 
     let on_edit_start = {
         let state = state.clone();
@@ -35,6 +56,7 @@ pub fn add_card(props: &AddCardProps) -> Html {
             if let Some(input) = node_ref.cast::<HtmlTextAreaElement>() {
                 if !input.value().is_empty() {
                     onedit.emit(TaskEvent::Add(TaskAddData {
+                        id: Uuid::new_v4(),
                         rank,
                         value: input.value(),
                     }));
@@ -44,6 +66,75 @@ pub fn add_card(props: &AddCardProps) -> Html {
         })
     };
 
+    let on_key_down = {
+        let node_ref = node_ref.clone();
+        Callback::from(move |e: KeyboardEvent| {
+            let input = match node_ref.cast::<HtmlTextAreaElement>() {
+                Some(input) => input,
+                None => return,
+            };
+            match e.key().as_str() {
+                "Escape" => {
+                    e.prevent_default();
+                    // an empty textarea adds nothing when it loses the focus
+                    input.set_value("");
+                    let _ = input.blur();
+                }
+                "Enter" if e.ctrl_key() || e.meta_key() => {
+                    e.prevent_default();
+                    let _ = input.blur();
+                }
+                _ => {}
+            }
+        })
+    };
+
+    // offers to split a pasted list into one task per item
+    let on_paste = {
+        let rank = props.rank;
+        let onbatch = props.onbatch.clone();
+        let state = state.clone();
+        let node_ref = node_ref.clone();
+        Callback::from(move |e: Event| {
+            let input = match node_ref.cast::<HtmlTextAreaElement>() {
+                Some(input) if input.value().is_empty() => input,
+                _ => return,
+            };
+            // `ClipboardEvent` is still an unstable API in web-sys
+            let text = js_sys::Reflect::get(&e, &"clipboardData".into())
+                .ok()
+                .and_then(|data| data.dyn_into::<DataTransfer>().ok())
+                .and_then(|data| data.get_data("text/plain").ok())
+                .unwrap_or_default();
+            let items = format::split_list(&text);
+            if items.len() < 2 {
+                return;
+            }
+            let message = format!("{}件のタスクに分けて追加しますか？", items.len());
+            if !gloo::dialogs::confirm(&message) {
+                return;
+            }
+
+            e.prevent_default();
+            let mut events = vec![];
+            for item in items {
+                let id = Uuid::new_v4();
+                events.push(TaskEvent::Add(TaskAddData {
+                    id,
+                    rank,
+                    value: item.value,
+                }));
+                if item.done {
+                    events.push(TaskEvent::Complete(TaskCompleteData { id }));
+                }
+            }
+            onbatch.emit(events);
+            // keeps the blur from adding the text as well
+            input.set_value("");
+            state.set(AddCardState::None);
+        })
+    };
+
     {
         let node_ref = node_ref.clone();
         use_effect(move || {
@@ -60,6 +151,8 @@ pub fn add_card(props: &AddCardProps) -> Html {
                 <textarea type="text" class="card"
                     ref={node_ref.clone()}
                     onblur={on_edit_end}
+                    onpaste={on_paste}
+                    onkeydown={on_key_down}
                 />
             }
         }
@@ -1,11 +1,27 @@
+use crate::card::keyboard::*;
 use crate::card::util::*;
 use crate::prelude::*;
+use gloo::timers::callback::Timeout;
 use std::rc::Rc;
 use uuid::Uuid;
-use web_sys::HtmlTextAreaElement;
+use web_sys::{Element, HtmlElement, HtmlTextAreaElement, PointerEvent};
 use yew::prelude::*;
 use yewdux::prelude::*;
 
+/// Cards left in the same rank for this many days are shown as stale.
+const STALE_DAYS: u32 = 7;
+
+const DAY_MS: f64 = 24.0 * 60.0 * 60.0 * 1000.0;
+
+/// A mouse picks up a card once it moves this far with the button down.
+const DRAG_THRESHOLD_PX: f64 = 4.0;
+
+/// A touch or pen picks up a card after holding still for this long.
+const LONG_PRESS_MS: u32 = 400;
+
+/// A touch moving farther than this before the long press scrolls instead.
+const LONG_PRESS_SLOP_PX: f64 = 8.0;
+
 #[derive(Properties, PartialEq)]
 pub struct CardProps {
     pub id: Uuid,
@@ -19,35 +35,74 @@ enum CardState {
     Editting,
 }
 
+/// A pointer pressed on a card that has not picked it up yet.
+struct Press {
+    pointer_id: i32,
+    touch: bool,
+    /// Where the press started, in page coordinates.
+    start: (i32, i32),
+    /// The latest position, in page coordinates.
+    pos: (i32, i32),
+    /// The pointer position relative to the card.
+    offset: (i32, i32),
+}
+
 pub struct Card {
     state: CardState,
     node_ref: NodeRef,
+    press: Option<Press>,
+    long_press: Option<Timeout>,
     dispatch_drag_initial_pos: Dispatch<DraggingInitialPos>,
+    dispatch_drag_mouse_pos: Dispatch<DraggingMousePos>,
+    dispatch_drag_pointer: Dispatch<DraggingPointerId>,
     dispatch_drag_card_id: Dispatch<DraggingCardId>,
     dispatch_drag_index: Dispatch<DraggingPositionIndex>,
+    dispatch_drag_rank: Dispatch<DraggingPositionRank>,
+    dispatch_focused: Dispatch<FocusedCardId>,
 }
 
 pub enum CardMsg {
     EditStart,
     EditEnd,
+    /// Ends editing without saving, back to the focused card.
+    EditCancel,
+    /// Saves from the keyboard, back to the focused card.
+    EditCommit,
+    ToggleDone,
+
+    KeyDown(KeyboardEvent),
+    EditKeyDown(KeyboardEvent),
+    Focus,
+    Blur,
+    FocusChanged,
 
-    MouseDown(MouseEvent),
-    MouseMove(MouseEvent),
-    MouseEnter(MouseEvent),
-    MouseLeave(MouseEvent),
+    PointerDown(PointerEvent),
+    PointerMove(PointerEvent),
+    PointerUp(PointerEvent),
+    PointerCancel,
+    ContextMenu(MouseEvent),
+    /// Starts dragging the pressed card.
+    PickUp,
 }
 
 impl Component for Card {
     type Message = CardMsg;
     type Properties = CardProps;
 
-    fn create(_ctx: &Context<Self>) -> Self {
+    fn create(ctx: &Context<Self>) -> Self {
+        let on_focus_changed = ctx.link().callback(move |_| CardMsg::FocusChanged);
         Self {
             state: CardState::None,
             node_ref: NodeRef::default(),
+            press: None,
+            long_press: None,
             dispatch_drag_initial_pos: Dispatch::subscribe(Callback::noop()),
+            dispatch_drag_mouse_pos: Dispatch::subscribe(Callback::noop()),
+            dispatch_drag_pointer: Dispatch::subscribe(Callback::noop()),
             dispatch_drag_card_id: Dispatch::subscribe(Callback::noop()),
             dispatch_drag_index: Dispatch::subscribe(Callback::noop()),
+            dispatch_drag_rank: Dispatch::subscribe(Callback::noop()),
+            dispatch_focused: Dispatch::subscribe(on_focus_changed),
         }
     }
 
@@ -59,6 +114,8 @@ impl Component for Card {
             EditStart => {
                 if self.dispatch_drag_card_id.get().id.is_none() {
                     self.state = CardState::Editting;
+                    // the textarea takes the focus until editing ends
+                    self.dispatch_focused.set(FocusedCardId { id: None });
                     true
                 } else {
                     false
@@ -66,6 +123,9 @@ impl Component for Card {
             }
 
             EditEnd => {
+                if self.state != CardState::Editting {
+                    return false;
+                }
                 let (db, _) = ctx
                     .link()
                     .context::<Rc<DataBase>>(Callback::noop())
@@ -79,6 +139,10 @@ impl Component for Card {
                     let input_value = input.value();
                     log::debug!("blur, id: {}, value: {}", id, input_value);
                     if input_value.is_empty() {
+                        if self.dispatch_focused.get().id == Some(id) {
+                            let next = survivor(&db, id);
+                            self.dispatch_focused.set(FocusedCardId { id: next });
+                        }
                         ctx.props()
                             .onedit
                             .emit(TaskEvent::Delete(TaskDeleteData { id }));
@@ -94,57 +158,225 @@ impl Component for Card {
                 true
             }
 
-            MouseDown(e) => {
-                log::debug!("[card] mouse_down");
-                let pos = Some((e.offset_x(), e.offset_y()));
-                self.dispatch_drag_initial_pos
-                    .reduce(|_| DraggingInitialPos { pos });
+            EditCancel => {
+                self.dispatch_focused.set(FocusedCardId { id: Some(id) });
+                self.state = CardState::None;
+                true
+            }
+
+            EditCommit => {
+                self.dispatch_focused.set(FocusedCardId { id: Some(id) });
+                ctx.link().send_message(EditEnd);
+                false
+            }
+
+            ToggleDone => {
+                let (db, _) = ctx
+                    .link()
+                    .context::<Rc<DataBase>>(Callback::noop())
+                    .expect("context to be set");
+                let done = db.get_by_id(id).map(|task| task.done).unwrap_or_default();
+                if done {
+                    ctx.props()
+                        .onedit
+                        .emit(TaskEvent::Uncomplete(TaskUncompleteData { id }));
+                } else {
+                    ctx.props()
+                        .onedit
+                        .emit(TaskEvent::Complete(TaskCompleteData { id }));
+                }
                 false
             }
-            MouseMove(e) => {
-                let id = ctx.props().id;
-                let initial_pos = self.dispatch_drag_initial_pos.get().pos;
-                let drag_id = self.dispatch_drag_card_id.get().id;
-                let drag_index = self.dispatch_drag_index.get().index;
-                // log::debug!("[card] mouse_move");
-
-                if initial_pos.is_some() && drag_id.is_none() {
-                    log::debug!("[card] start dragging");
-                    self.dispatch_drag_card_id
-                        .reduce(|_| DraggingCardId { id: Some(id) });
-                    let index = ctx.props().index;
-                    self.dispatch_drag_index
-                        .reduce(|_| DraggingPositionIndex { index: Some(index) });
+
+            KeyDown(e) => {
+                if e.ctrl_key() || e.meta_key() || self.state != CardState::None {
                     return false;
                 }
+                let (db, _) = ctx
+                    .link()
+                    .context::<Rc<DataBase>>(Callback::noop())
+                    .expect("context to be set");
 
-                let mut index = ctx.props().index;
-                if e.movement_y() > 0 && drag_index.is_some() {
-                    index += 1;
+                if let Some(arrow) = Arrow::from_key(&e.key()) {
+                    e.prevent_default();
+                    if e.alt_key() {
+                        if let Some(step) = step(&db, id, arrow) {
+                            ctx.props().onedit.emit(TaskEvent::Move(step));
+                        }
+                    } else if let Some(next) = neighbor(&db, id, arrow) {
+                        self.dispatch_focused.set(FocusedCardId { id: Some(next) });
+                    }
+                    return false;
                 }
-                if drag_index != Some(index) {
-                    self.dispatch_drag_index
-                        .reduce(|_| DraggingPositionIndex { index: Some(index) });
+
+                match e.key().as_str() {
+                    "Enter" | "F2" => {
+                        e.prevent_default();
+                        ctx.link().send_message(EditStart);
+                    }
+                    " " | "x" => {
+                        e.prevent_default();
+                        ctx.link().send_message(ToggleDone);
+                    }
+                    "Delete" | "Backspace" => {
+                        e.prevent_default();
+                        let next = survivor(&db, id);
+                        self.dispatch_focused.set(FocusedCardId { id: next });
+                        ctx.props()
+                            .onedit
+                            .emit(TaskEvent::Delete(TaskDeleteData { id }));
+                    }
+                    _ => {}
                 }
                 false
             }
-            MouseEnter(_) => {
-                // let index = ctx.props().index;
-                // self.dispatch_drag_index
-                //     .reduce(|_| DraggingPositionIndex { index: Some(index) });
+            EditKeyDown(e) => {
+                match e.key().as_str() {
+                    "Escape" => {
+                        e.prevent_default();
+                        ctx.link().send_message(EditCancel);
+                    }
+                    "Enter" if e.ctrl_key() || e.meta_key() => {
+                        e.prevent_default();
+                        ctx.link().send_message(EditCommit);
+                    }
+                    _ => {}
+                }
                 false
             }
-            MouseLeave(_) => {
-                // self.dispatch_drag_index
-                //     .reduce(|_| DraggingPositionIndex { index: None });
+            Focus => {
+                if self.dispatch_focused.get().id != Some(id) {
+                    self.dispatch_focused.set(FocusedCardId { id: Some(id) });
+                }
+                false
+            }
+            Blur => {
+                if self.dispatch_focused.get().id == Some(id) {
+                    self.dispatch_focused.set(FocusedCardId { id: None });
+                }
+                false
+            }
+            FocusChanged => {
+                self.focus_if_wanted(ctx);
+                false
+            }
+
+            PointerDown(e) => {
+                if !e.is_primary()
+                    || e.button() != 0
+                    || self.state != CardState::None
+                    || self.dispatch_drag_card_id.get().id.is_some()
+                {
+                    return false;
+                }
+                let element = match self.node_ref.cast::<Element>() {
+                    Some(element) => element,
+                    None => return false,
+                };
+                log::debug!("[card] pointer_down, type: {}", e.pointer_type());
+
+                // keeps the moves coming here even when the pointer leaves the card
+                let _ = element.set_pointer_capture(e.pointer_id());
+                let rect = element.get_bounding_client_rect();
+                let touch = e.pointer_type() != "mouse";
+                self.press = Some(Press {
+                    pointer_id: e.pointer_id(),
+                    touch,
+                    start: (e.page_x(), e.page_y()),
+                    pos: (e.page_x(), e.page_y()),
+                    offset: (
+                        e.client_x() - rect.left() as i32,
+                        e.client_y() - rect.top() as i32,
+                    ),
+                });
+                self.long_press = touch.then(|| {
+                    let link = ctx.link().clone();
+                    Timeout::new(LONG_PRESS_MS, move || link.send_message(PickUp))
+                });
+                false
+            }
+            PointerMove(e) => {
+                let press = match &mut self.press {
+                    Some(press) if press.pointer_id == e.pointer_id() => press,
+                    _ => return false,
+                };
+                press.pos = (e.page_x(), e.page_y());
+                let (dx, dy) = (press.pos.0 - press.start.0, press.pos.1 - press.start.1);
+                let distance = f64::from(dx).hypot(f64::from(dy));
+
+                if !press.touch {
+                    if distance >= DRAG_THRESHOLD_PX {
+                        ctx.link().send_message(PickUp);
+                    }
+                } else if distance > LONG_PRESS_SLOP_PX {
+                    // the list scrolls instead
+                    self.press = None;
+                    self.long_press = None;
+                }
+                false
+            }
+            PointerUp(e) => {
+                match &self.press {
+                    Some(press) if press.pointer_id == e.pointer_id() => {}
+                    _ => return false,
+                }
+                self.press = None;
+                self.long_press = None;
+                ctx.link().send_message(EditStart);
+                false
+            }
+            PointerCancel => {
+                self.press = None;
+                self.long_press = None;
+                false
+            }
+            ContextMenu(e) => {
+                // a long press opens the context menu on touch screens
+                if self.press.is_some() || self.dispatch_drag_card_id.get().id.is_some() {
+                    e.prevent_default();
+                }
+                false
+            }
+            PickUp => {
+                let press = match self.press.take() {
+                    Some(press) => press,
+                    None => return false,
+                };
+                self.long_press = None;
+                log::debug!("[card] start dragging");
+
+                let (db, _) = ctx
+                    .link()
+                    .context::<Rc<DataBase>>(Callback::noop())
+                    .expect("context to be set");
+                let rank = db.get_by_id(id).map(|task| task.rank);
+                let index = ctx.props().index;
+                self.dispatch_drag_initial_pos
+                    .reduce(|_| DraggingInitialPos {
+                        pos: Some(press.offset),
+                    });
+                self.dispatch_drag_mouse_pos.reduce(|_| DraggingMousePos {
+                    pos: Some(press.pos),
+                });
+                self.dispatch_drag_rank
+                    .reduce(|_| DraggingPositionRank { rank });
+                self.dispatch_drag_index
+                    .reduce(|_| DraggingPositionIndex { index: Some(index) });
+                self.dispatch_drag_pointer.reduce(|_| DraggingPointerId {
+                    id: Some(press.pointer_id),
+                });
+                self.dispatch_drag_card_id
+                    .reduce(|_| DraggingCardId { id: Some(id) });
                 false
             }
         }
     }
 
-    fn rendered(&mut self, _ctx: &Context<Self>, _first_render: bool) {
+    fn rendered(&mut self, ctx: &Context<Self>, _first_render: bool) {
         if let Some(input) = self.node_ref.cast::<HtmlTextAreaElement>() {
             input.focus();
+        } else {
+            self.focus_if_wanted(ctx);
         }
     }
 
@@ -153,17 +385,26 @@ impl Component for Card {
             .link()
             .context::<Rc<DataBase>>(Callback::noop())
             .expect("context to be set");
-        let value = db
-            .get_by_id(ctx.props().id)
-            .map(move |task| task.value.clone())
-            .expect("!!!error!!!");
+        let task = db.get_by_id(ctx.props().id).expect("!!!error!!!");
+        let (value, done) = (task.value.clone(), task.done);
+        let age_days = task
+            .moved_at
+            .or(task.created_at)
+            .map(|since| ((js_sys::Date::now() - since) / DAY_MS).max(0.0) as u32);
 
-        let on_mouse_up = ctx.link().callback(move |_| CardMsg::EditStart);
+        let on_toggle_done = ctx.link().callback(move |_| CardMsg::ToggleDone);
+        // keeps the card from entering edit mode or being picked up
+        let on_check_pointer_down = Callback::from(move |e: PointerEvent| e.stop_propagation());
         let on_edit_end = ctx.link().callback(move |_| CardMsg::EditEnd);
-        let on_mouse_down = ctx.link().callback(move |e| CardMsg::MouseDown(e));
-        let on_mouse_move = ctx.link().callback(move |e| CardMsg::MouseMove(e));
-        let on_mouse_enter = ctx.link().callback(move |e| CardMsg::MouseEnter(e));
-        let on_mouse_leave = ctx.link().callback(move |e| CardMsg::MouseLeave(e));
+        let on_edit_key_down = ctx.link().callback(CardMsg::EditKeyDown);
+        let on_key_down = ctx.link().callback(CardMsg::KeyDown);
+        let on_focus = ctx.link().callback(move |_| CardMsg::Focus);
+        let on_blur = ctx.link().callback(move |_| CardMsg::Blur);
+        let on_pointer_down = ctx.link().callback(CardMsg::PointerDown);
+        let on_pointer_move = ctx.link().callback(CardMsg::PointerMove);
+        let on_pointer_up = ctx.link().callback(CardMsg::PointerUp);
+        let on_pointer_cancel = ctx.link().callback(move |_| CardMsg::PointerCancel);
+        let on_context_menu = ctx.link().callback(CardMsg::ContextMenu);
 
         match self.state {
             CardState::Editting => {
@@ -172,24 +413,74 @@ impl Component for Card {
                         ref={self.node_ref.clone()}
                         value={value}
                         onblur={on_edit_end}
+                        onkeydown={on_edit_key_down}
                     />
                 }
             }
             _ => {
+                let mut class = classes!("card");
+                if done {
+                    class.push("done");
+                }
+                if !done && age_days.unwrap_or_default() >= STALE_DAYS {
+                    class.push("stale");
+                }
+
                 html! {
-                    <p class="card"
+                    <p class={class}
                         ref={self.node_ref.clone()}
-                        onmousedown={on_mouse_down}
-                        onmousemove={on_mouse_move}
-                        onmouseup={on_mouse_up}
-                        onmouseenter={on_mouse_enter}
-                        onmouseleave={on_mouse_leave}
+                        tabindex="0"
+                        data-index={ctx.props().index.to_string()}
+                        onkeydown={on_key_down}
+                        onfocus={on_focus}
+                        onblur={on_blur}
+                        onpointerdown={on_pointer_down}
+                        onpointermove={on_pointer_move}
+                        onpointerup={on_pointer_up}
+                        onpointercancel={on_pointer_cancel}
+                        oncontextmenu={on_context_menu}
                     >
+                    <button class="card-check"
+                        onpointerdown={on_check_pointer_down}
+                        onclick={on_toggle_done}
+                    />
+                    if let Some(days) = age_days.filter(|&days| days > 0) {
+                        <span class="card-age" title={timestamps_title(task)}>
+                            { format!("{}日", days) }
+                        </span>
+                    }
                     // { format!("id: {}, value: ", props.id) }
-                    { task_value_as_html(&value) }
+                    { task_value_as_html(&value, done) }
                     </p>
                 }
             }
         }
     }
 }
+
+impl Card {
+    /// Moves the keyboard focus to this card if it is the focused one.
+    fn focus_if_wanted(&self, ctx: &Context<Self>) {
+        if self.dispatch_focused.get().id != Some(ctx.props().id) {
+            return;
+        }
+        if let Some(element) = self.node_ref.cast::<HtmlElement>() {
+            let active = gloo::utils::document().active_element();
+            if active.as_deref() != Some(element.as_ref()) {
+                let _ = element.focus();
+            }
+        }
+    }
+}
+
+fn timestamps_title(task: &TaskData) -> String {
+    [
+        ("作成", task.created_at),
+        ("編集", task.edited_at),
+        ("移動", task.moved_at),
+    ]
+    .into_iter()
+    .filter_map(|(label, timestamp)| Some(format!("{}: {}", label, format_timestamp(timestamp?))))
+    .collect::<Vec<_>>()
+    .join("\n")
+}
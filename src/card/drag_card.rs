@@ -8,9 +8,8 @@ use yewdux::prelude::*;
 pub fn dragging_card() -> Html {
     let db = use_context::<Rc<DataBase>>().expect("context to be set");
     let card_id = use_store::<DraggingCardId>().0.id;
-    let value = card_id
-        .map(|id| db.get_by_id(id).map(|task| task.value.clone()))
-        .unwrap_or_default()
+    let (value, done) = card_id
+        .and_then(|id| db.get_by_id(id).map(|task| (task.value.clone(), task.done)))
         .unwrap_or_default();
 
     let mut class = classes!("card", "dragging-card");
@@ -26,7 +25,7 @@ pub fn dragging_card() -> Html {
 
     html! {
         <p class={class} style={style}>
-        { task_value_as_html(&value) }
+        { task_value_as_html(&value, done) }
         </p>
     }
 }
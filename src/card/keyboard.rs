@@ -0,0 +1,100 @@
+use crate::prelude::*;
+use uuid::Uuid;
+
+/// An arrow key. The columns are laid out with Primary on the left and
+/// Secondary above Other on the right, so going down from the bottom of
+/// Secondary continues at the top of Other.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Arrow {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Arrow {
+    pub fn from_key(key: &str) -> Option<Self> {
+        match key {
+            "ArrowUp" => Some(Arrow::Up),
+            "ArrowDown" => Some(Arrow::Down),
+            "ArrowLeft" => Some(Arrow::Left),
+            "ArrowRight" => Some(Arrow::Right),
+            _ => None,
+        }
+    }
+}
+
+/// The card to focus after `arrow` is pressed on the card `id`.
+pub(crate) fn neighbor(db: &DataBase, id: Uuid, arrow: Arrow) -> Option<Uuid> {
+    let (rank, index) = locate(db, id)?;
+    let len = |rank| db.get_by_rank(rank).len();
+    let (rank, index) = match (arrow, rank) {
+        (Arrow::Up, _) if index > 0 => (rank, index - 1),
+        (Arrow::Up, TaskRank::Other) => (
+            TaskRank::Secondary,
+            len(TaskRank::Secondary).checked_sub(1)?,
+        ),
+        (Arrow::Down, _) if index + 1 < len(rank) => (rank, index + 1),
+        (Arrow::Down, TaskRank::Secondary) => (TaskRank::Other, 0),
+        (Arrow::Right, TaskRank::Primary) => {
+            let rank = [TaskRank::Secondary, TaskRank::Other]
+                .into_iter()
+                .find(|&rank| len(rank) > 0)?;
+            (rank, index.min(len(rank) - 1))
+        }
+        (Arrow::Left, TaskRank::Secondary | TaskRank::Other) => (
+            TaskRank::Primary,
+            index.min(len(TaskRank::Primary).checked_sub(1)?),
+        ),
+        _ => return None,
+    };
+    db.get_by_rank(rank).get(index).map(|task| task.id())
+}
+
+/// The move that carries the card `id` one step toward `arrow`.
+pub(crate) fn step(db: &DataBase, id: Uuid, arrow: Arrow) -> Option<TaskMoveData> {
+    let (rank, index) = locate(db, id)?;
+    let len = |rank| db.get_by_rank(rank).len();
+    let (rank, index) = match (arrow, rank) {
+        (Arrow::Up, _) if index > 0 => (rank, Some(index - 1)),
+        (Arrow::Up, TaskRank::Other) => (TaskRank::Secondary, None),
+        (Arrow::Down, _) if index + 1 < len(rank) => (rank, Some(index + 1)),
+        (Arrow::Down, TaskRank::Secondary) => (TaskRank::Other, Some(0)),
+        (Arrow::Right, TaskRank::Primary) => (
+            TaskRank::Secondary,
+            Some(index.min(len(TaskRank::Secondary))),
+        ),
+        (Arrow::Left, TaskRank::Secondary | TaskRank::Other) => {
+            (TaskRank::Primary, Some(index.min(len(TaskRank::Primary))))
+        }
+        _ => return None,
+    };
+    Some(TaskMoveData { id, rank, index })
+}
+
+/// The card to focus once the card `id` is gone: the next one in its rank,
+/// or else the previous one.
+pub(crate) fn survivor(db: &DataBase, id: Uuid) -> Option<Uuid> {
+    let (rank, index) = locate(db, id)?;
+    let tasks = db.get_by_rank(rank);
+    tasks
+        .get(index + 1)
+        .or_else(|| index.checked_sub(1).and_then(|i| tasks.get(i)))
+        .map(|task| task.id())
+}
+
+/// The first card, for when no card has the focus yet.
+pub(crate) fn first_card(db: &DataBase) -> Option<Uuid> {
+    TaskRank::ALL
+        .into_iter()
+        .find_map(|rank| db.get_by_rank(rank).first().map(|task| task.id()))
+}
+
+fn locate(db: &DataBase, id: Uuid) -> Option<(TaskRank, usize)> {
+    let rank = db.get_by_id(id)?.rank;
+    let index = db
+        .get_by_rank(rank)
+        .iter()
+        .position(|task| task.id() == id)?;
+    Some((rank, index))
+}
@@ -1,17 +1,32 @@
 use itertools::{EitherOrBoth, Itertools};
 use lazy_static::lazy_static;
 use regex::*;
+use wasm_bindgen::JsValue;
 use yew::prelude::*;
 
-pub(super) fn task_value_as_html(value: &str) -> Html {
-    html! {
+pub(crate) fn task_value_as_html(value: &str, done: bool) -> Html {
+    let lines = html! {
         <>
         // { for value.lines().map(|l| html!(<>{l.to_string()}<br/></>)) }
         { for value.lines().map(|l| line_to_html(l)) }
         </>
+    };
+
+    if done {
+        html! { <s>{ lines }</s> }
+    } else {
+        lines
     }
 }
 
+/// Formats milliseconds since the UNIX epoch as a local date and time.
+pub(crate) fn format_timestamp(timestamp: f64) -> String {
+    js_sys::Date::new(&JsValue::from_f64(timestamp))
+        .to_locale_string("ja-JP", &JsValue::UNDEFINED)
+        .as_string()
+        .unwrap_or_default()
+}
+
 fn line_to_html(value: &str) -> Html {
     lazy_static! {
         static ref RE: Regex =
@@ -1,7 +1,17 @@
-mod database;
-mod taskdata;
-mod taskrank;
+mod archive;
+mod board;
+mod eventlog;
+mod history;
+mod storage;
+mod sync;
+mod transfer;
+mod trash;
 
-pub use database::*;
-pub use taskdata::*;
-pub use taskrank::*;
+pub use archive::*;
+pub use board::*;
+pub use eventlog::*;
+pub use history::*;
+pub use storage::*;
+pub use sync::*;
+pub use transfer::*;
+pub use trash::*;
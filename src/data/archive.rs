@@ -0,0 +1,128 @@
+use crate::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Tasks removed from the board by a single burn.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BurnPage {
+    pub id: Uuid,
+    /// Milliseconds since the UNIX epoch.
+    pub burned_at: f64,
+    /// Burned tasks in their final order. `TaskData::rank` is the column they
+    /// were burned from.
+    pub tasks: Vec<TaskData>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct BurnArchive {
+    #[serde(skip)]
+    board: Uuid,
+    pages: Vec<BurnPage>,
+}
+
+impl BurnArchive {
+    pub fn load_from_local_storage(board: Uuid) -> Self {
+        let mut archive =
+            load_or_backup::<BurnArchive>(&board_key(board, "archive")).unwrap_or_default();
+        archive.board = board;
+        archive
+    }
+
+    pub fn save_to_local_storage(&self) {
+        if let Err(e) = save(&board_key(self.board, "archive"), self) {
+            log::error!("{}", e);
+        }
+    }
+
+    /// Adds a page of `tasks` burned at `now`.
+    pub fn push(&mut self, tasks: Vec<TaskData>, now: f64) {
+        if tasks.is_empty() {
+            return;
+        }
+
+        self.pages.push(BurnPage {
+            id: Uuid::new_v4(),
+            burned_at: now,
+            tasks,
+        });
+    }
+
+    /// Drops the tasks that are back on the board of `db`, e.g. after an undo,
+    /// and the pages left empty. Returns whether there were any.
+    pub fn remove_restored(&mut self, db: &DataBase) -> bool {
+        let mut removed = false;
+        for page in &mut self.pages {
+            let len = page.tasks.len();
+            page.tasks.retain(|task| db.get_by_id(task.id()).is_none());
+            removed |= page.tasks.len() != len;
+        }
+        self.pages.retain(|page| !page.tasks.is_empty());
+        removed
+    }
+
+    /// Every burned task with the time it was burned at, oldest first.
+    pub fn burned_tasks(&self) -> impl Iterator<Item = (f64, &TaskData)> {
+        self.pages
+            .iter()
+            .flat_map(|page| page.tasks.iter().map(move |task| (page.burned_at, task)))
+    }
+
+    /// Pages from the most recent burn to the oldest.
+    pub fn pages(&self) -> impl Iterator<Item = &BurnPage> {
+        self.pages.iter().rev()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    #[test]
+    fn burned_pages_are_saved() {
+        set_storage(Rc::new(MemoryBackend::default()));
+        let board = Uuid::new_v4();
+        let mut archive = BurnArchive::load_from_local_storage(board);
+        archive.push(vec![], 1.0);
+        assert_eq!(archive.pages().count(), 0);
+
+        let tasks = vec![
+            TaskData::new(TaskRank::Primary, "first"),
+            TaskData::new(TaskRank::Primary, "second"),
+        ];
+        archive.push(tasks.clone(), 1.0);
+        archive.push(vec![TaskData::new(TaskRank::Primary, "third")], 2.0);
+        archive.save_to_local_storage();
+
+        let loaded = BurnArchive::load_from_local_storage(board);
+        assert_eq!(loaded, archive);
+        let burned_at = loaded
+            .pages()
+            .map(|page| page.burned_at)
+            .collect::<Vec<_>>();
+        assert_eq!(burned_at, [2.0, 1.0]);
+        let burned = loaded
+            .burned_tasks()
+            .map(|(at, task)| (at, task.value.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(burned, [(1.0, "first"), (1.0, "second"), (2.0, "third")]);
+        assert_eq!(loaded.pages().last().unwrap().tasks, tasks);
+    }
+
+    #[test]
+    fn tasks_back_on_the_board_are_removed() {
+        let mut db = DataBase::default();
+        let mut archive = BurnArchive::default();
+        let first = TaskData::new(TaskRank::Primary, "first");
+        let second = TaskData::new(TaskRank::Primary, "second");
+        archive.push(vec![first.clone()], 1.0);
+        archive.push(vec![second.clone()], 2.0);
+        assert!(!archive.remove_restored(&db));
+
+        db.restore_task(&first, first.rank, 3.0);
+        assert!(archive.remove_restored(&db));
+        let pages = archive.pages().collect::<Vec<_>>();
+        assert_eq!(pages.len(), 1);
+        assert_eq!(pages[0].tasks, [second]);
+    }
+}
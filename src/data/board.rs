@@ -0,0 +1,191 @@
+use crate::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Keys a board was stored under before there were multiple boards.
+pub const LEGACY_KEYS: [&str; 5] = ["tasks", "snapshot", "events", "history", "archive"];
+
+pub fn board_key(board: Uuid, key: &str) -> String {
+    format!("board.{}.{}", board, key)
+}
+
+/// Every key stored for `board`, without the `board_key` prefix.
+fn stored_keys(board: Uuid) -> Vec<String> {
+    let prefix = board_key(board, "");
+    storage()
+        .keys()
+        .into_iter()
+        .filter_map(|key| key.strip_prefix(&prefix).map(str::to_owned))
+        .collect()
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Board {
+    pub id: Uuid,
+    pub name: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BoardCollection {
+    boards: Vec<Board>,
+}
+
+impl BoardCollection {
+    pub fn load_from_local_storage() -> Self {
+        if let Some(boards) = load_or_backup::<BoardCollection>("boards") {
+            if !boards.boards.is_empty() {
+                return boards;
+            }
+        }
+
+        // adopt the data saved before there were multiple boards
+        let board = Board {
+            id: Uuid::new_v4(),
+            name: "Burner List".to_owned(),
+        };
+        let storage = storage();
+        for key in LEGACY_KEYS {
+            if let Some(raw) = storage.get(key) {
+                if storage.set(&board_key(board.id, key), &raw).is_ok() {
+                    storage.remove(key);
+                }
+            }
+        }
+
+        let boards = Self {
+            boards: vec![board],
+        };
+        boards.save_to_local_storage();
+        boards
+    }
+
+    pub fn save_to_local_storage(&self) {
+        if let Err(e) = save("boards", self) {
+            log::error!("{}", e);
+        }
+    }
+
+    pub fn boards(&self) -> &[Board] {
+        &self.boards
+    }
+
+    pub fn get(&self, id: Uuid) -> Option<&Board> {
+        self.boards.iter().find(|board| board.id == id)
+    }
+
+    pub fn first(&self) -> &Board {
+        &self.boards[0]
+    }
+
+    pub fn create(&mut self, name: &str) -> Uuid {
+        let id = Uuid::new_v4();
+        self.boards.push(Board {
+            id,
+            name: name.to_owned(),
+        });
+        id
+    }
+
+    pub fn rename(&mut self, id: Uuid, name: &str) -> bool {
+        if let Some(board) = self.boards.iter_mut().find(|board| board.id == id) {
+            board.name = name.to_owned();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Creates a new board holding a copy of the tasks of `id`. The copy is a
+    /// replica of its own, so that edits to either board never share a stamp
+    /// if they are merged. The history, the trash and the sync settings stay
+    /// with the original board.
+    pub fn duplicate(&mut self, id: Uuid, name: &str) -> Option<Uuid> {
+        self.get(id)?;
+        let (_, db) = EventLog::load_from_local_storage(id);
+        let mut copy = DataBase::default();
+        copy.merge(&db);
+
+        let new_id = self.create(name);
+        EventLog::create(new_id, &copy);
+        Some(new_id)
+    }
+
+    /// Deletes `id` and its stored data. The last board cannot be deleted.
+    pub fn delete(&mut self, id: Uuid) -> bool {
+        if self.boards.len() <= 1 || self.get(id).is_none() {
+            return false;
+        }
+
+        self.boards.retain(|board| board.id != id);
+        let storage = storage();
+        for key in stored_keys(id) {
+            storage.remove(&board_key(id, &key));
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    #[test]
+    fn duplicates_copy_the_tasks_as_another_replica() {
+        set_storage(Rc::new(MemoryBackend::default()));
+        let mut boards = BoardCollection::load_from_local_storage();
+        let board = boards.first().id;
+        let (mut log, mut db) = EventLog::load_from_local_storage(board);
+        let event = TaskEvent::Add(TaskAddData {
+            id: Uuid::new_v4(),
+            rank: TaskRank::Secondary,
+            value: "task".to_owned(),
+        });
+        db.apply_event(&event, 1.0);
+        log.append(event, 1.0, &db);
+        let mut history = History::load_from_local_storage(board);
+        history.record(DataBase::default());
+        history.save_to_local_storage();
+
+        let copy = boards.duplicate(board, "copy").unwrap();
+        let (_, copied) = EventLog::load_from_local_storage(copy);
+        let ids = |db: &DataBase| {
+            TaskRank::ALL
+                .into_iter()
+                .flat_map(|rank| db.get_by_rank(rank))
+                .map(TaskData::id)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(ids(&copied), ids(&db));
+        assert_eq!(copied.get_by_rank(TaskRank::Secondary).len(), 1);
+
+        let replica = |db: &DataBase| serde_json::to_value(db).unwrap()["replica"].clone();
+        assert_ne!(replica(&copied), replica(&db));
+        assert!(storage().get(&board_key(board, "history")).is_some());
+        assert!(storage().get(&board_key(copy, "history")).is_none());
+        assert!(boards.duplicate(Uuid::new_v4(), "missing").is_none());
+    }
+
+    #[test]
+    fn tasks_saved_before_boards_are_adopted() {
+        set_storage(Rc::new(MemoryBackend::default()));
+        let baseline = r#"{"tasks":[
+            {"id":"3f2b8c1e-6a4d-4c55-9b1e-0d7f2a9e4c11","rank":"Primary","value":"first"},
+            {"id":"5d8e2f7a-9c1b-4a3e-b6d4-2e8f1c0a7b55","rank":"Other","value":"second"}
+        ]}"#;
+        storage().set("tasks", baseline).unwrap();
+
+        let boards = BoardCollection::load_from_local_storage();
+        assert_eq!(boards.boards().len(), 1);
+        assert!(storage().get("tasks").is_none());
+
+        let (_, db) = EventLog::load_from_local_storage(boards.first().id);
+        let values = db
+            .tasks()
+            .map(|task| task.value.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(values.len(), 2);
+        assert!(values.contains(&"first") && values.contains(&"second"));
+        assert_eq!(BoardCollection::load_from_local_storage(), boards);
+    }
+}
@@ -0,0 +1,320 @@
+use crate::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Number of events appended between two snapshots.
+const COMPACTION_INTERVAL: u64 = 50;
+
+/// Number of entries kept in the log as an audit trail, including the ones
+/// already folded into the snapshot.
+const LOG_LIMIT: usize = 1000;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TaskLogEntry {
+    pub seq: u64,
+    /// Milliseconds since the UNIX epoch.
+    pub timestamp: f64,
+    /// The `EventLog` instance, i.e. the browser tab, that appended the entry.
+    #[serde(default)]
+    pub origin: Uuid,
+    pub event: TaskEvent,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Snapshot {
+    #[serde(default)]
+    id: Uuid,
+    seq: u64,
+    /// The board without its tasks, or with all of them in snapshots written
+    /// before the tasks were stored separately.
+    db: DataBase,
+    /// Tasks stored under their own keys, see `task_key`.
+    #[serde(default)]
+    tasks: Vec<Uuid>,
+}
+
+fn task_key(board: Uuid, id: Uuid) -> String {
+    board_key(board, &format!("task.{}", id))
+}
+
+/// Reads the board saved before there was an event log. A new board starts
+/// with a greeting.
+fn load_legacy(board: Uuid) -> DataBase {
+    if let Some(db) = load_or_backup::<DataBase>(&board_key(board, "tasks")) {
+        db
+    } else {
+        let mut db = DataBase::default();
+
+        // dummy
+        db.insert_task(TaskData::new(
+            TaskRank::Primary,
+            "Hello! BurnerList is a simple, intentionally constrained list.",
+        ));
+
+        db
+    }
+}
+
+/// The event log of a board, shared through the local storage by every tab
+/// that has the board open.
+///
+/// The stored log is the source of truth: the `DataBase` is whatever replaying
+/// it yields, so entries are ordered by `seq` and a later entry wins over an
+/// earlier one that touched the same task. When two tabs append with the same
+/// `seq`, the entry that reached the storage first is kept and the other tab
+/// re-appends its entry after it.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct EventLog {
+    #[serde(skip)]
+    board: Uuid,
+    #[serde(skip)]
+    origin: Uuid,
+    /// Stamps of the tasks as last written by `compact`, so that unchanged
+    /// tasks are not written again.
+    #[serde(skip)]
+    written: HashMap<Uuid, TaskStamps>,
+    #[serde(default)]
+    snapshot_id: Uuid,
+    snapshot_seq: u64,
+    entries: Vec<TaskLogEntry>,
+}
+
+impl EventLog {
+    /// Restores the `DataBase` from the latest snapshot and replays the events
+    /// appended after it.
+    pub fn load_from_local_storage(board: Uuid) -> (Self, DataBase) {
+        let mut log = Self::load_stored(board);
+        let (db, has_snapshot) = log.restore();
+        if !has_snapshot {
+            log.compact(&db);
+        }
+
+        (log, db)
+    }
+
+    /// Starts the log of a new `board` holding `db`.
+    pub fn create(board: Uuid, db: &DataBase) -> Self {
+        let mut log = Self {
+            board,
+            origin: Uuid::new_v4(),
+            ..Default::default()
+        };
+        log.compact(db);
+        log
+    }
+
+    pub fn save_to_local_storage(&self) {
+        if let Err(e) = save(&board_key(self.board, "events"), self) {
+            log::error!("{}", e);
+        }
+    }
+
+    /// Catches up with entries other tabs have written to the storage. Returns
+    /// the rebuilt `DataBase` if anything changed.
+    pub fn sync(&mut self) -> Option<DataBase> {
+        let stored = Self::load_stored(self.board);
+        // the origin tells apart two tabs that appended the same seq
+        if stored.snapshot_id == self.snapshot_id && stored.head() == self.head() {
+            return None;
+        }
+
+        // entries of this tab overwritten by another tab appending the same seq
+        let first_stored_seq = stored.entries.first().map(|entry| entry.seq);
+        let lost = self
+            .entries
+            .iter()
+            .filter(|entry| entry.origin == self.origin)
+            .filter(|entry| match first_stored_seq {
+                Some(first) if entry.seq >= first => !stored
+                    .entries
+                    .iter()
+                    .any(|e| e.seq == entry.seq && e.origin == entry.origin),
+                _ => entry.seq > stored.last_seq(),
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+
+        log::debug!("[eventlog] sync, {} lost entries", lost.len());
+
+        self.snapshot_id = stored.snapshot_id;
+        self.snapshot_seq = stored.snapshot_seq;
+        self.entries = stored.entries;
+        let (mut db, _) = self.restore();
+        for entry in lost {
+            if db.apply_event(&entry.event, entry.timestamp) {
+                self.append(entry.event, entry.timestamp, &db);
+            }
+        }
+
+        Some(db)
+    }
+
+    /// Appends `event` to the log and compacts it into a new snapshot of `db`
+    /// when enough events have piled up. `db` must already reflect `event`.
+    pub fn append(&mut self, event: TaskEvent, timestamp: f64, db: &DataBase) {
+        let seq = self.last_seq() + 1;
+        self.entries.push(TaskLogEntry {
+            seq,
+            timestamp,
+            origin: self.origin,
+            event,
+        });
+
+        if seq - self.snapshot_seq >= COMPACTION_INTERVAL {
+            self.compact(db);
+        } else {
+            self.save_to_local_storage();
+        }
+    }
+
+    /// Writes `db` as the snapshot of the current head of the log. Used when the
+    /// state changed without an event, e.g. by undo and redo.
+    ///
+    /// Each task is stored under its own key and only written when it changed,
+    /// so a snapshot costs about as much as the tasks edited since the last one.
+    pub fn compact(&mut self, db: &DataBase) {
+        // the tasks go first, so that the snapshot never refers to a missing one
+        let mut written = HashMap::new();
+        for task in db.tasks() {
+            if self.written.get(&task.id()) != Some(&task.stamps()) {
+                if let Err(e) = save(&task_key(self.board, task.id()), task) {
+                    log::error!("{}", e);
+                    return;
+                }
+            }
+            written.insert(task.id(), task.stamps());
+        }
+
+        let seq = self.last_seq();
+        let snapshot = Snapshot {
+            id: Uuid::new_v4(),
+            seq,
+            db: db.without_tasks(),
+            tasks: written.keys().copied().collect(),
+        };
+        if let Err(e) = save(&board_key(self.board, "snapshot"), &snapshot) {
+            log::error!("{}", e);
+            return;
+        }
+
+        let storage = storage();
+        for id in self.written.keys().filter(|id| !written.contains_key(id)) {
+            storage.remove(&task_key(self.board, *id));
+        }
+        self.written = written;
+
+        self.snapshot_id = snapshot.id;
+        self.snapshot_seq = seq;
+        if self.entries.len() > LOG_LIMIT {
+            self.entries.drain(..self.entries.len() - LOG_LIMIT);
+        }
+        self.save_to_local_storage();
+    }
+
+    fn load_stored(board: Uuid) -> Self {
+        let mut log = load_or_backup::<EventLog>(&board_key(board, "events")).unwrap_or_default();
+        log.board = board;
+        log.origin = Uuid::new_v4();
+        log
+    }
+
+    /// Rebuilds the `DataBase` from the stored snapshot and the pending entries.
+    /// Also returns whether a snapshot was found.
+    fn restore(&mut self) -> (DataBase, bool) {
+        let snapshot = load_or_backup::<Snapshot>(&board_key(self.board, "snapshot"));
+        let has_snapshot = snapshot.is_some();
+        self.written.clear();
+        let (id, seq, mut db) = match snapshot {
+            Some(Snapshot {
+                id,
+                seq,
+                mut db,
+                tasks,
+            }) => {
+                for task_id in tasks {
+                    match load_or_backup::<TaskData>(&task_key(self.board, task_id)) {
+                        Some(task) => {
+                            self.written.insert(task_id, task.stamps());
+                            db.insert_task(task);
+                        }
+                        None => {
+                            log::error!("[eventlog] task {} of the snapshot is missing", task_id)
+                        }
+                    }
+                }
+                (id, seq, db)
+            }
+            None => (Uuid::nil(), 0, load_legacy(self.board)),
+        };
+
+        // the snapshot is written before the log, so it wins if they disagree
+        self.snapshot_id = id;
+        self.snapshot_seq = seq;
+        for entry in self.pending() {
+            db.apply_event(&entry.event, entry.timestamp);
+        }
+
+        (db, has_snapshot)
+    }
+
+    fn last_seq(&self) -> u64 {
+        self.entries
+            .last()
+            .map(|entry| entry.seq)
+            .unwrap_or(self.snapshot_seq)
+    }
+
+    /// The seq and origin of the last entry.
+    fn head(&self) -> (u64, Option<Uuid>) {
+        let origin = self.entries.last().map(|entry| entry.origin);
+        (self.last_seq(), origin)
+    }
+
+    fn pending(&self) -> impl Iterator<Item = &TaskLogEntry> {
+        let snapshot_seq = self.snapshot_seq;
+        self.entries
+            .iter()
+            .filter(move |entry| entry.seq > snapshot_seq)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    fn add(log: &mut EventLog, db: &mut DataBase, value: &str) -> Uuid {
+        let id = Uuid::new_v4();
+        let event = TaskEvent::Add(TaskAddData {
+            id,
+            rank: TaskRank::Primary,
+            value: value.to_owned(),
+        });
+        assert!(db.apply_event(&event, 1.0));
+        log.append(event, 1.0, db);
+        id
+    }
+
+    #[test]
+    fn sync_recovers_an_entry_overwritten_with_the_same_seq() {
+        set_storage(Rc::new(MemoryBackend::default()));
+        let board = Uuid::new_v4();
+        let (mut log1, mut db1) = EventLog::load_from_local_storage(board);
+        let (mut log2, mut db2) = EventLog::load_from_local_storage(board);
+
+        // both tabs append seq 1, the second write wins in the storage
+        let id1 = add(&mut log1, &mut db1, "first tab");
+        let id2 = add(&mut log2, &mut db2, "second tab");
+        assert_eq!(log1.last_seq(), log2.last_seq());
+
+        let db1 = log1.sync().expect("the overwritten entry is detected");
+        assert!(db1.get_by_id(id1).is_some());
+        assert!(db1.get_by_id(id2).is_some());
+
+        let db2 = log2.sync().expect("the re-appended entry is picked up");
+        assert!(db2.get_by_id(id1).is_some());
+        assert!(log1.sync().is_none());
+        assert!(log2.sync().is_none());
+    }
+}
@@ -0,0 +1,165 @@
+use crate::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use uuid::Uuid;
+
+const HISTORY_LIMIT: usize = 50;
+
+/// Number of the most recent states written to the storage on each side. The
+/// older ones are kept in memory only, so that saving stays cheap.
+const STORED_LIMIT: usize = 10;
+
+#[derive(Clone, Default, Deserialize)]
+pub struct History {
+    #[serde(skip)]
+    board: Uuid,
+    undo: VecDeque<DataBase>,
+    redo: VecDeque<DataBase>,
+}
+
+impl History {
+    pub fn load_from_local_storage(board: Uuid) -> Self {
+        let mut history =
+            load_or_backup::<History>(&board_key(board, "history")).unwrap_or_default();
+        history.board = board;
+        history
+    }
+
+    pub fn save_to_local_storage(&self) {
+        let stored = StoredHistory {
+            undo: recent(&self.undo),
+            redo: recent(&self.redo),
+        };
+        if let Err(e) = save(&board_key(self.board, "history"), &stored) {
+            log::error!("{}", e);
+        }
+    }
+
+    /// Records the state of `db` before a mutation. Clears the redo stack.
+    pub fn record(&mut self, db: DataBase) {
+        self.undo.push_back(db);
+        if self.undo.len() > HISTORY_LIMIT {
+            self.undo.pop_front();
+        }
+        self.redo.clear();
+    }
+
+    /// Reverts `db` to the state before the last mutation, as a change made
+    /// at `timestamp`.
+    pub fn undo(&mut self, db: &mut DataBase, timestamp: f64) -> bool {
+        if let Some(prev) = self.undo.pop_back() {
+            self.redo.push_back(db.clone());
+            db.revert(&prev, timestamp);
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn redo(&mut self, db: &mut DataBase, timestamp: f64) -> bool {
+        if let Some(next) = self.redo.pop_back() {
+            self.undo.push_back(db.clone());
+            db.revert(&next, timestamp);
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// The part of `History` written to the storage.
+#[derive(Serialize)]
+struct StoredHistory<'a> {
+    undo: Vec<&'a DataBase>,
+    redo: Vec<&'a DataBase>,
+}
+
+fn recent(states: &VecDeque<DataBase>) -> Vec<&DataBase> {
+    states
+        .iter()
+        .skip(states.len().saturating_sub(STORED_LIMIT))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    fn add(db: &mut DataBase, value: &str, timestamp: f64) {
+        db.apply_event(
+            &TaskEvent::Add(TaskAddData {
+                id: Uuid::new_v4(),
+                rank: TaskRank::Primary,
+                value: value.to_owned(),
+            }),
+            timestamp,
+        );
+    }
+
+    fn values(db: &DataBase) -> Vec<String> {
+        db.get_by_rank(TaskRank::Primary)
+            .into_iter()
+            .map(|task| task.value.clone())
+            .collect()
+    }
+
+    #[test]
+    fn undo_and_redo_survive_a_reload() {
+        set_storage(Rc::new(MemoryBackend::default()));
+        let board = Uuid::new_v4();
+        let mut history = History::load_from_local_storage(board);
+        let mut db = DataBase::default();
+        for i in 0..3 {
+            history.record(db.clone());
+            add(&mut db, &format!("task {}", i), f64::from(i));
+        }
+        assert!(history.undo(&mut db, 10.0));
+        history.save_to_local_storage();
+
+        let mut loaded = History::load_from_local_storage(board);
+        assert!(loaded.redo(&mut db, 11.0));
+        assert_eq!(values(&db), ["task 0", "task 1", "task 2"]);
+        assert!(loaded.undo(&mut db, 12.0));
+        assert!(loaded.undo(&mut db, 13.0));
+        assert_eq!(values(&db), ["task 0"]);
+    }
+
+    #[test]
+    fn undoing_a_deletion_restores_the_task() {
+        let mut history = History::default();
+        let mut db = DataBase::default();
+        add(&mut db, "first", 1.0);
+        add(&mut db, "second", 1.0);
+        let task = db.get_by_rank(TaskRank::Primary)[0].clone();
+
+        history.record(db.clone());
+        db.apply_event(&TaskEvent::Delete(TaskDeleteData { id: task.id() }), 2.0);
+        assert!(history.undo(&mut db, 3.0));
+
+        let restored = db.get_by_id(task.id()).expect("the same id");
+        assert_eq!(restored.position(), task.position());
+        assert_eq!(restored.created_at, task.created_at);
+        assert_eq!(values(&db), ["first", "second"]);
+    }
+
+    #[test]
+    fn only_the_recent_states_are_saved() {
+        set_storage(Rc::new(MemoryBackend::default()));
+        let board = Uuid::new_v4();
+        let mut history = History::load_from_local_storage(board);
+        let mut db = DataBase::default();
+        for i in 0..HISTORY_LIMIT + 5 {
+            history.record(db.clone());
+            add(&mut db, &format!("task {}", i), i as f64);
+        }
+        assert_eq!(history.undo.len(), HISTORY_LIMIT);
+        history.save_to_local_storage();
+
+        let mut loaded = History::load_from_local_storage(board);
+        assert_eq!(loaded.undo.len(), STORED_LIMIT);
+        assert!(loaded.redo.is_empty());
+        while loaded.undo(&mut db, 1000.0) {}
+        assert_eq!(values(&db).len(), HISTORY_LIMIT + 5 - STORED_LIMIT);
+    }
+}
@@ -0,0 +1,144 @@
+mod indexeddb;
+mod local;
+mod memory;
+
+pub use indexeddb::*;
+pub use local::*;
+pub use memory::*;
+
+use serde::{de::DeserializeOwned, Serialize};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Key-value store the data layer persists everything to. Values are JSON
+/// strings.
+pub trait TaskStorage {
+    fn get(&self, key: &str) -> Option<String>;
+
+    fn set(&self, key: &str, value: &str) -> Result<(), String>;
+
+    fn remove(&self, key: &str);
+
+    fn keys(&self) -> Vec<String>;
+
+    /// Calls `callback` with the key whenever another tab changes a value,
+    /// after the new value can be read. `None` stops watching.
+    fn watch(&self, callback: Option<Box<dyn Fn(String)>>);
+
+    /// Calls `callback` with a message whenever a write fails, including
+    /// writes that fail in the background after `set` returned. `None` stops
+    /// reporting.
+    fn on_error(&self, callback: Option<Box<dyn Fn(String)>>);
+}
+
+thread_local! {
+    static STORAGE: RefCell<Rc<dyn TaskStorage>> = RefCell::new(Rc::new(LocalStorageBackend::default()));
+}
+
+/// The storage chosen at startup. Defaults to the local storage.
+pub fn storage() -> Rc<dyn TaskStorage> {
+    STORAGE.with(|storage| storage.borrow().clone())
+}
+
+pub fn set_storage(storage: Rc<dyn TaskStorage>) {
+    STORAGE.with(|current| *current.borrow_mut() = storage);
+}
+
+/// Opens the storage named by the `storage` query parameter (`indexeddb`,
+/// `local` or `memory`). Without it, IndexedDB is used where available.
+pub async fn open_storage() -> Rc<dyn TaskStorage> {
+    let requested = web_sys::window()
+        .and_then(|window| window.location().search().ok())
+        .and_then(|search| {
+            search
+                .trim_start_matches('?')
+                .split('&')
+                .find_map(|pair| pair.strip_prefix("storage="))
+                .map(str::to_owned)
+        });
+
+    match requested.as_deref() {
+        Some("local") => Rc::new(LocalStorageBackend::default()),
+        Some("memory") => Rc::new(MemoryBackend::default()),
+        _ => match IndexedDbBackend::open().await {
+            Ok(storage) => Rc::new(storage),
+            Err(e) => {
+                log::warn!(
+                    "[storage] IndexedDB is unavailable, using the local storage: {:?}",
+                    e
+                );
+                Rc::new(LocalStorageBackend::default())
+            }
+        },
+    }
+}
+
+/// Reads `key` from the storage. If the stored value cannot be read, it is
+/// copied to a backup key, `<key>.backup-<n>`, so that the next save does not
+/// destroy it.
+pub fn load_or_backup<T: DeserializeOwned>(key: &str) -> Option<T> {
+    let raw = storage().get(key)?;
+    match serde_json::from_str::<T>(&raw) {
+        Ok(value) => Some(value),
+        Err(e) => {
+            log::error!("[storage] failed to load `{}`: {}", key, e);
+            backup(key, &raw);
+            None
+        }
+    }
+}
+
+pub fn save<T: Serialize>(key: &str, value: &T) -> Result<(), String> {
+    let raw = serde_json::to_string(value).map_err(|e| e.to_string())?;
+    storage().set(key, &raw)
+}
+
+fn backup(key: &str, raw: &str) {
+    let storage = storage();
+    let backup_key = (1..)
+        .map(|n| format!("{}.backup-{}", key, n))
+        .find(|backup_key| storage.get(backup_key).is_none())
+        .expect("a free backup key");
+    match storage.set(&backup_key, raw) {
+        Ok(_) => log::warn!("[storage] saved unreadable `{}` as `{}`", key, backup_key),
+        Err(e) => log::error!("[storage] failed to back up `{}`: {}", key, e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Value {
+        name: String,
+        count: u32,
+    }
+
+    #[test]
+    fn saved_values_load_back() {
+        set_storage(Rc::new(MemoryBackend::default()));
+        let value = Value {
+            name: "タスク".to_owned(),
+            count: 3,
+        };
+
+        save("key", &value).unwrap();
+        assert_eq!(load_or_backup::<Value>("key"), Some(value));
+        assert_eq!(load_or_backup::<Value>("missing"), None);
+    }
+
+    #[test]
+    fn unreadable_values_are_backed_up() {
+        set_storage(Rc::new(MemoryBackend::default()));
+
+        storage().set("key", "{ broken").unwrap();
+        assert_eq!(load_or_backup::<Value>("key"), None);
+        storage().set("key", "[]").unwrap();
+        assert_eq!(load_or_backup::<Value>("key"), None);
+
+        assert_eq!(storage().get("key.backup-1").as_deref(), Some("{ broken"));
+        assert_eq!(storage().get("key.backup-2").as_deref(), Some("[]"));
+    }
+}
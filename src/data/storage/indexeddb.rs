@@ -0,0 +1,234 @@
+use super::{MemoryBackend, TaskStorage};
+use crate::data::LEGACY_KEYS;
+use gloo::events::EventListener;
+use gloo::storage::{LocalStorage, Storage};
+use js_sys::{Array, Promise};
+use std::cell::RefCell;
+use std::rc::Rc;
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{BroadcastChannel, IdbDatabase, IdbRequest, IdbTransactionMode, MessageEvent};
+
+const DATABASE_NAME: &str = "burner-list";
+const STORE_NAME: &str = "storage";
+
+type Handler = Rc<RefCell<Option<Box<dyn Fn(String)>>>>;
+
+/// IndexedDB, which holds far more than the local storage. Everything is read
+/// into memory when opened, so reads are synchronous, and writes are sent to
+/// IndexedDB in the background one key at a time.
+///
+/// IndexedDB has no `storage` event, so the tabs tell each other about their
+/// writes over a `BroadcastChannel`.
+///
+/// A write that fails once its transaction runs, e.g. because the quota is
+/// exceeded, is reported to the `on_error` callback.
+pub struct IndexedDbBackend {
+    db: IdbDatabase,
+    cache: Rc<MemoryBackend>,
+    channel: BroadcastChannel,
+    watcher: Handler,
+    on_error: Handler,
+    _listener: EventListener,
+}
+
+impl IndexedDbBackend {
+    /// Opens the database and reads everything in it. The first time, the
+    /// data in the local storage is copied over. It is left in place so that
+    /// `?storage=local` still finds it.
+    pub async fn open() -> Result<Self, JsValue> {
+        let factory = web_sys::window()
+            .and_then(|window| window.indexed_db().ok().flatten())
+            .ok_or_else(|| JsValue::from_str("IndexedDB is not supported"))?;
+
+        let request = factory.open_with_u32(DATABASE_NAME, 1)?;
+        let upgrading = request.clone();
+        let on_upgrade_needed = Closure::once_into_js(move || {
+            if let Some(db) = upgrading
+                .result()
+                .ok()
+                .and_then(|db| db.dyn_into::<IdbDatabase>().ok())
+            {
+                if let Err(e) = db.create_object_store(STORE_NAME) {
+                    log::error!("[storage] failed to create the object store: {:?}", e);
+                }
+            }
+        });
+        request.set_onupgradeneeded(Some(on_upgrade_needed.unchecked_ref()));
+        let db = wait(&request).await?.dyn_into::<IdbDatabase>()?;
+
+        let cache = Rc::new(MemoryBackend::default());
+        let store = db
+            .transaction_with_str(STORE_NAME)?
+            .object_store(STORE_NAME)?;
+        let keys = Array::from(&wait(&store.get_all_keys()?).await?);
+        let values = Array::from(&wait(&store.get_all()?).await?);
+        for (key, value) in keys.iter().zip(values.iter()) {
+            if let (Some(key), Some(value)) = (key.as_string(), value.as_string()) {
+                let _ = cache.set(&key, &value);
+            }
+        }
+
+        let channel = BroadcastChannel::new(DATABASE_NAME)?;
+        let watcher = Handler::default();
+        let listener = {
+            let cache = cache.clone();
+            let watcher = watcher.clone();
+            EventListener::new(&channel, "message", move |e| {
+                let data = match e.dyn_ref::<MessageEvent>() {
+                    Some(e) => Array::from(&e.data()),
+                    None => return,
+                };
+                let key = match data.get(0).as_string() {
+                    Some(key) => key,
+                    None => return,
+                };
+                match data.get(1).as_string() {
+                    Some(value) => {
+                        let _ = cache.set(&key, &value);
+                    }
+                    None => cache.remove(&key),
+                }
+                if let Some(callback) = watcher.borrow().as_ref() {
+                    callback(key);
+                }
+            })
+        };
+
+        let storage = Self {
+            db,
+            cache,
+            channel,
+            watcher,
+            on_error: Handler::default(),
+            _listener: listener,
+        };
+        if storage.cache.keys().is_empty() {
+            storage.import_local_storage();
+        }
+        Ok(storage)
+    }
+
+    /// Copies the boards from the local storage, including the data saved
+    /// before there were multiple boards, which `BoardCollection` adopts.
+    fn import_local_storage(&self) {
+        let local = LocalStorage::raw();
+        let length = local.length().unwrap_or(0);
+        for key in (0..length).filter_map(|i| local.key(i).ok().flatten()) {
+            let board_data =
+                key == "boards" || key.starts_with("board.") || LEGACY_KEYS.contains(&key.as_str());
+            if !board_data {
+                continue;
+            }
+            if let Ok(Some(value)) = local.get_item(&key) {
+                let _ = self.set(&key, &value);
+            }
+        }
+    }
+
+    /// Writes `value` to `key` in IndexedDB, or deletes the key with `None`,
+    /// and lets the other tabs know. Fails if the write cannot be started; a
+    /// write failing later is reported to `on_error`.
+    fn write(&self, key: &str, value: Option<&str>) -> Result<(), String> {
+        let key_value = JsValue::from_str(key);
+        let transaction = self
+            .db
+            .transaction_with_str_and_mode(STORE_NAME, IdbTransactionMode::Readwrite)
+            .map_err(|e| format!("failed to write `{}`: {:?}", key, e))?;
+
+        // a failed request aborts the transaction, as does running out of quota
+        let failed = transaction.clone();
+        let on_error = self.on_error.clone();
+        let owned_key = key.to_owned();
+        let on_abort = Closure::once_into_js(move || {
+            let reason = failed
+                .error()
+                .map(|e| e.message())
+                .unwrap_or_else(|| "aborted".to_owned());
+            report(
+                &on_error,
+                format!("failed to write `{}`: {}", owned_key, reason),
+            );
+        });
+        transaction.set_onabort(Some(on_abort.unchecked_ref()));
+
+        transaction
+            .object_store(STORE_NAME)
+            .and_then(|store| match value {
+                Some(value) => store.put_with_key(&JsValue::from_str(value), &key_value),
+                None => store.delete(&key_value),
+            })
+            .map_err(|e| format!("failed to write `{}`: {:?}", key, e))?;
+
+        let message = Array::of2(
+            &key_value,
+            &value.map(JsValue::from_str).unwrap_or(JsValue::NULL),
+        );
+        if let Err(e) = self.channel.post_message(&message) {
+            log::error!("[storage] failed to notify other tabs: {:?}", e);
+        }
+        Ok(())
+    }
+}
+
+impl TaskStorage for IndexedDbBackend {
+    fn get(&self, key: &str) -> Option<String> {
+        self.cache.get(key)
+    }
+
+    fn set(&self, key: &str, value: &str) -> Result<(), String> {
+        self.cache.set(key, value)?;
+        self.write(key, Some(value))
+            .inspect_err(|e| report(&self.on_error, e.clone()))
+    }
+
+    fn remove(&self, key: &str) {
+        self.cache.remove(key);
+        if let Err(e) = self.write(key, None) {
+            report(&self.on_error, e);
+        }
+    }
+
+    fn keys(&self) -> Vec<String> {
+        self.cache.keys()
+    }
+
+    fn watch(&self, callback: Option<Box<dyn Fn(String)>>) {
+        *self.watcher.borrow_mut() = callback;
+    }
+
+    fn on_error(&self, callback: Option<Box<dyn Fn(String)>>) {
+        *self.on_error.borrow_mut() = callback;
+    }
+}
+
+fn report(on_error: &Handler, message: String) {
+    log::error!("[storage] {}", message);
+    if let Some(callback) = on_error.borrow().as_ref() {
+        callback(message);
+    }
+}
+
+/// Resolves to the result of `request` once it succeeds.
+async fn wait(request: &IdbRequest) -> Result<JsValue, JsValue> {
+    let promise = Promise::new(&mut |resolve, reject| {
+        let succeeded = request.clone();
+        let on_success = Closure::once_into_js(move || {
+            let result = succeeded.result().unwrap_or(JsValue::UNDEFINED);
+            let _ = resolve.call1(&JsValue::UNDEFINED, &result);
+        });
+        let failed = request.clone();
+        let on_error = Closure::once_into_js(move || {
+            let error = failed
+                .error()
+                .ok()
+                .flatten()
+                .map(JsValue::from)
+                .unwrap_or(JsValue::UNDEFINED);
+            let _ = reject.call1(&JsValue::UNDEFINED, &error);
+        });
+        request.set_onsuccess(Some(on_success.unchecked_ref()));
+        request.set_onerror(Some(on_error.unchecked_ref()));
+    });
+    JsFuture::from(promise).await
+}
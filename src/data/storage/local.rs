@@ -0,0 +1,60 @@
+use super::TaskStorage;
+use gloo::events::EventListener;
+use gloo::storage::{LocalStorage, Storage};
+use std::cell::RefCell;
+use wasm_bindgen::JsCast;
+use web_sys::StorageEvent;
+
+type Handler = RefCell<Option<Box<dyn Fn(String)>>>;
+
+/// The browser's local storage. Small (about 5 MB) and every write is
+/// synchronous, but other tabs see the changes through `storage` events.
+#[derive(Default)]
+pub struct LocalStorageBackend {
+    watcher: RefCell<Option<EventListener>>,
+    on_error: Handler,
+}
+
+impl TaskStorage for LocalStorageBackend {
+    fn get(&self, key: &str) -> Option<String> {
+        LocalStorage::raw().get_item(key).ok().flatten()
+    }
+
+    fn set(&self, key: &str, value: &str) -> Result<(), String> {
+        let result = LocalStorage::raw()
+            .set_item(key, value)
+            .map_err(|e| format!("failed to write `{}`: {:?}", key, e));
+        if let (Err(e), Some(callback)) = (&result, self.on_error.borrow().as_ref()) {
+            callback(e.clone());
+        }
+        result
+    }
+
+    fn remove(&self, key: &str) {
+        let _ = LocalStorage::raw().remove_item(key);
+    }
+
+    fn keys(&self) -> Vec<String> {
+        let storage = LocalStorage::raw();
+        let length = storage.length().unwrap_or(0);
+        (0..length)
+            .filter_map(|i| storage.key(i).ok().flatten())
+            .collect()
+    }
+
+    fn watch(&self, callback: Option<Box<dyn Fn(String)>>) {
+        let window = web_sys::window().expect("window");
+        let listener = callback.map(|callback| {
+            EventListener::new(&window, "storage", move |e| {
+                if let Some(key) = e.dyn_ref::<StorageEvent>().and_then(StorageEvent::key) {
+                    callback(key);
+                }
+            })
+        });
+        *self.watcher.borrow_mut() = listener;
+    }
+
+    fn on_error(&self, callback: Option<Box<dyn Fn(String)>>) {
+        *self.on_error.borrow_mut() = callback;
+    }
+}
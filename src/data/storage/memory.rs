@@ -0,0 +1,35 @@
+use super::TaskStorage;
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+
+/// Keeps everything in memory until the page is closed. Also the cache of
+/// `IndexedDbBackend`.
+#[derive(Default)]
+pub struct MemoryBackend {
+    values: RefCell<BTreeMap<String, String>>,
+}
+
+impl TaskStorage for MemoryBackend {
+    fn get(&self, key: &str) -> Option<String> {
+        self.values.borrow().get(key).cloned()
+    }
+
+    fn set(&self, key: &str, value: &str) -> Result<(), String> {
+        self.values
+            .borrow_mut()
+            .insert(key.to_owned(), value.to_owned());
+        Ok(())
+    }
+
+    fn remove(&self, key: &str) {
+        self.values.borrow_mut().remove(key);
+    }
+
+    fn keys(&self) -> Vec<String> {
+        self.values.borrow().keys().cloned().collect()
+    }
+
+    fn watch(&self, _callback: Option<Box<dyn Fn(String)>>) {}
+
+    fn on_error(&self, _callback: Option<Box<dyn Fn(String)>>) {}
+}
@@ -0,0 +1,189 @@
+use crate::prelude::*;
+use gloo::net::http::Request;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use uuid::Uuid;
+
+/// Server a board is synchronized with. The protocol is described in
+/// `server/README.md`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SyncConfig {
+    /// Base URL of the server, e.g. `http://localhost:8787`.
+    pub endpoint: String,
+    pub token: String,
+    /// Board on the server, shared by every device that syncs it.
+    pub remote: Uuid,
+}
+
+/// A batch of changes pushed to the server by `client`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SyncChange {
+    /// Lets the server ignore a change pushed again after a lost response.
+    pub id: Uuid,
+    pub client: Uuid,
+    pub db: DataBase,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SyncPullResponse {
+    pub cursor: u64,
+    pub changes: Vec<SyncChange>,
+}
+
+#[derive(Deserialize)]
+struct SyncPushResponse {
+    cursor: u64,
+}
+
+/// Sync progress of a board. Changes are queued in the local storage until
+/// the server accepts them, so edits made offline are pushed once it is
+/// reachable again.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct SyncState {
+    #[serde(skip)]
+    board: Uuid,
+    config: Option<SyncConfig>,
+    /// This device, as the sender of the pushed changes.
+    client: Uuid,
+    /// Position in the remote board up to which changes have been pulled.
+    cursor: u64,
+    /// Clock of the `DataBase` when the last change was queued.
+    queued: Option<Stamp>,
+    queue: VecDeque<SyncChange>,
+}
+
+impl SyncState {
+    pub fn load_from_local_storage(board: Uuid) -> Self {
+        let mut state = load_or_backup::<SyncState>(&board_key(board, "sync")).unwrap_or_default();
+        state.board = board;
+        if state.client.is_nil() {
+            state.client = Uuid::new_v4();
+        }
+        state
+    }
+
+    pub fn save_to_local_storage(&self) {
+        if let Err(e) = save(&board_key(self.board, "sync"), self) {
+            log::error!("{}", e);
+        }
+    }
+
+    pub fn config(&self) -> Option<&SyncConfig> {
+        self.config.as_ref()
+    }
+
+    pub fn pending(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Links the board to a remote board, or unlinks it with `None`. Starts
+    /// over from the beginning of the remote board.
+    pub fn configure(&mut self, config: Option<SyncConfig>) {
+        self.config = config;
+        self.cursor = 0;
+        self.queued = None;
+        self.queue.clear();
+        self.save_to_local_storage();
+    }
+
+    /// Queues whatever changed in `db` since the last call.
+    pub fn enqueue(&mut self, db: &DataBase) {
+        if self.config.is_none() {
+            return;
+        }
+
+        if let Some(changes) = db.changes_since(self.queued) {
+            self.queue.push_back(SyncChange {
+                id: Uuid::new_v4(),
+                client: self.client,
+                db: changes,
+            });
+            self.queued = Some(db.clock());
+            self.save_to_local_storage();
+        }
+    }
+
+    /// The oldest change the server has not accepted yet.
+    pub fn next_push(&self) -> Option<&SyncChange> {
+        self.queue.front()
+    }
+
+    pub fn pushed(&mut self, id: Uuid) {
+        if self.queue.front().map(|change| change.id) == Some(id) {
+            self.queue.pop_front();
+            self.save_to_local_storage();
+        }
+    }
+
+    /// Merges the pulled changes of the other devices into `db`. Returns
+    /// whether there were any. Local changes must be queued beforehand, as
+    /// the merged ones are not queued again.
+    pub fn pulled(&mut self, response: SyncPullResponse, db: &mut DataBase) -> bool {
+        let mut merged = false;
+        for change in response.changes {
+            if change.client != self.client {
+                db.merge(&change.db);
+                merged = true;
+            }
+        }
+        if merged {
+            self.queued = Some(db.clock());
+        }
+
+        self.cursor = response.cursor;
+        self.save_to_local_storage();
+        merged
+    }
+
+    pub fn cursor(&self) -> u64 {
+        self.cursor
+    }
+}
+
+impl SyncConfig {
+    /// Sends `change` and returns the cursor the server assigned to it.
+    pub async fn push(&self, change: &SyncChange) -> Result<u64, String> {
+        let response = Request::post(&self.changes_url())
+            .header("Authorization", &format!("Bearer {}", self.token))
+            .json(change)
+            .map_err(|e| e.to_string())?
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        if !response.ok() {
+            return Err(format!("push failed: HTTP {}", response.status()));
+        }
+
+        let response = response
+            .json::<SyncPushResponse>()
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(response.cursor)
+    }
+
+    /// Fetches the changes after `cursor`.
+    pub async fn pull(&self, cursor: u64) -> Result<SyncPullResponse, String> {
+        let response = Request::get(&self.changes_url())
+            .query([("since", cursor.to_string())])
+            .header("Authorization", &format!("Bearer {}", self.token))
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        if !response.ok() {
+            return Err(format!("pull failed: HTTP {}", response.status()));
+        }
+
+        response
+            .json::<SyncPullResponse>()
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    fn changes_url(&self) -> String {
+        format!(
+            "{}/boards/{}/changes",
+            self.endpoint.trim_end_matches('/'),
+            self.remote
+        )
+    }
+}
@@ -0,0 +1,211 @@
+use crate::prelude::*;
+use gloo::file::{Blob, ObjectUrl};
+use gloo::timers::callback::Timeout;
+use wasm_bindgen::JsCast;
+use web_sys::HtmlAnchorElement;
+
+/// A file format boards are exported to and imported from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BoardFormat {
+    Json,
+    Markdown,
+    TodoTxt,
+    ICalendar,
+    Csv,
+}
+
+impl BoardFormat {
+    pub const ALL: [BoardFormat; 5] = [
+        BoardFormat::Json,
+        BoardFormat::Markdown,
+        BoardFormat::TodoTxt,
+        BoardFormat::ICalendar,
+        BoardFormat::Csv,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            BoardFormat::Json => "JSON",
+            BoardFormat::Markdown => "Markdown",
+            BoardFormat::TodoTxt => "todo.txt",
+            BoardFormat::ICalendar => "iCalendar",
+            BoardFormat::Csv => "CSV",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            BoardFormat::Json => "json",
+            BoardFormat::Markdown => "md",
+            BoardFormat::TodoTxt => "txt",
+            BoardFormat::ICalendar => "ics",
+            BoardFormat::Csv => "csv",
+        }
+    }
+
+    pub fn mime_type(self) -> &'static str {
+        match self {
+            BoardFormat::Json => "application/json",
+            BoardFormat::Markdown => "text/markdown",
+            BoardFormat::TodoTxt => "text/plain",
+            BoardFormat::ICalendar => "text/calendar",
+            BoardFormat::Csv => "text/csv",
+        }
+    }
+
+    /// Guesses the format from the extension of `file_name`, defaulting to
+    /// JSON.
+    pub fn from_file_name(file_name: &str) -> Self {
+        let extension = file_name
+            .rsplit_once('.')
+            .map(|(_, extension)| extension.to_ascii_lowercase())
+            .unwrap_or_default();
+        match extension.as_str() {
+            "md" | "markdown" => BoardFormat::Markdown,
+            "txt" => BoardFormat::TodoTxt,
+            "ics" | "ical" => BoardFormat::ICalendar,
+            "csv" => BoardFormat::Csv,
+            _ => BoardFormat::Json,
+        }
+    }
+
+    /// Writes the board. Only CSV includes the burned tasks of `archive`.
+    pub fn export(self, db: &DataBase, archive: &BurnArchive) -> String {
+        match self {
+            BoardFormat::Json => {
+                serde_json::to_string_pretty(db).expect("DataBase to be serializable")
+            }
+            BoardFormat::Markdown => format::to_markdown(db),
+            BoardFormat::TodoTxt => format::to_todotxt(db),
+            BoardFormat::ICalendar => format::to_ical(db),
+            BoardFormat::Csv => format::to_csv(db, archive.burned_tasks()),
+        }
+    }
+
+    /// Reads a board exported in this format, by any version of the app.
+    pub fn import(self, text: &str, timestamp: f64) -> Result<DataBase, String> {
+        match self {
+            BoardFormat::Json => serde_json::from_str::<DataBase>(text)
+                .map_err(|e| format!("ボードの JSON ではありません: {}", e)),
+            BoardFormat::Markdown => format::from_markdown(text, timestamp),
+            BoardFormat::TodoTxt => format::from_todotxt(text, timestamp),
+            BoardFormat::ICalendar => format::from_ical(text, timestamp),
+            BoardFormat::Csv => format::from_csv(text, timestamp),
+        }
+    }
+}
+
+/// How an imported board is combined with the current one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImportMode {
+    /// The current tasks are replaced by the imported ones.
+    Replace,
+    /// The imported tasks are merged in, like the changes of another device.
+    Merge,
+}
+
+/// A difference between two states of a board.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TaskChange {
+    Added(TaskData),
+    Removed(TaskData),
+    Changed {
+        before: Box<TaskData>,
+        after: Box<TaskData>,
+    },
+}
+
+/// An imported file, checked and ready to be applied.
+#[derive(Clone, PartialEq)]
+pub struct ImportPreview {
+    pub file_name: String,
+    replace: DataBase,
+    merge: DataBase,
+    replace_changes: Vec<TaskChange>,
+    merge_changes: Vec<TaskChange>,
+}
+
+impl ImportPreview {
+    /// Works out what importing `imported`, read from `file_name`, into
+    /// `current` would change.
+    pub fn new(current: &DataBase, file_name: &str, imported: DataBase) -> Self {
+        let mut merged = current.clone();
+        merged.merge(&imported);
+        Self {
+            file_name: file_name.to_owned(),
+            replace_changes: diff(current, &imported),
+            merge_changes: diff(current, &merged),
+            replace: imported,
+            merge: merged,
+        }
+    }
+
+    pub fn changes(&self, mode: ImportMode) -> &[TaskChange] {
+        match mode {
+            ImportMode::Replace => &self.replace_changes,
+            ImportMode::Merge => &self.merge_changes,
+        }
+    }
+
+    /// Applies the import to `db` as a change made at `timestamp`, so that it
+    /// can be undone and is synced like any other edit.
+    pub fn apply(&self, db: &mut DataBase, mode: ImportMode, timestamp: f64) {
+        let target = match mode {
+            ImportMode::Replace => &self.replace,
+            ImportMode::Merge => &self.merge,
+        };
+        db.revert(target, timestamp);
+    }
+}
+
+/// The tasks that differ between `before` and `after`, in the order of
+/// `after`, followed by the removed ones.
+pub fn diff(before: &DataBase, after: &DataBase) -> Vec<TaskChange> {
+    let mut changes = vec![];
+    for rank in TaskRank::ALL {
+        for task in after.get_by_rank(rank) {
+            match before.get_by_id(task.id()) {
+                None => changes.push(TaskChange::Added(task.clone())),
+                Some(old)
+                    if old.value != task.value
+                        || old.rank != task.rank
+                        || old.position() != task.position()
+                        || old.done != task.done =>
+                {
+                    changes.push(TaskChange::Changed {
+                        before: Box::new(old.clone()),
+                        after: Box::new(task.clone()),
+                    })
+                }
+                Some(_) => {}
+            }
+        }
+    }
+    for rank in TaskRank::ALL {
+        for task in before.get_by_rank(rank) {
+            if after.get_by_id(task.id()).is_none() {
+                changes.push(TaskChange::Removed(task.clone()));
+            }
+        }
+    }
+    changes
+}
+
+/// Lets the browser save `content` as a file named `file_name`.
+pub fn download(file_name: &str, mime_type: &str, content: &str) {
+    let url = ObjectUrl::from(Blob::new_with_options(content, Some(mime_type)));
+    let anchor = gloo::utils::document()
+        .create_element("a")
+        .ok()
+        .and_then(|element| element.dyn_into::<HtmlAnchorElement>().ok());
+    match anchor {
+        Some(anchor) => {
+            anchor.set_href(&url);
+            anchor.set_download(file_name);
+            anchor.click();
+        }
+        None => log::error!("[transfer] failed to create a download link"),
+    }
+    // the download starts asynchronously, so the url must outlive this call
+    Timeout::new(60_000, move || drop(url)).forget();
+}
@@ -0,0 +1,186 @@
+use crate::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+const DAY_MS: f64 = 24.0 * 60.0 * 60.0 * 1000.0;
+
+/// A task removed from the board by deleting it.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DeletedTask {
+    /// Milliseconds since the UNIX epoch.
+    pub deleted_at: f64,
+    /// The task as it was deleted. `TaskData::rank` is the column it was
+    /// deleted from.
+    pub task: TaskData,
+}
+
+/// Recently deleted tasks, kept for `retention_days` so they can be restored.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Trash {
+    #[serde(skip)]
+    board: Uuid,
+    #[serde(default = "Trash::default_retention_days")]
+    retention_days: u32,
+    tasks: Vec<DeletedTask>,
+}
+
+impl Default for Trash {
+    fn default() -> Self {
+        Self {
+            board: Uuid::default(),
+            retention_days: Self::default_retention_days(),
+            tasks: vec![],
+        }
+    }
+}
+
+impl Trash {
+    fn default_retention_days() -> u32 {
+        30
+    }
+
+    /// Loads the trash of `board`, dropping what expired before `now`.
+    pub fn load_from_local_storage(board: Uuid, now: f64) -> Self {
+        let mut trash = load_or_backup::<Trash>(&board_key(board, "trash")).unwrap_or_default();
+        trash.board = board;
+        trash.purge(now);
+        trash
+    }
+
+    pub fn save_to_local_storage(&self) {
+        if let Err(e) = save(&board_key(self.board, "trash"), self) {
+            log::error!("{}", e);
+        }
+    }
+
+    /// Keeps `task`, deleted at `now`.
+    pub fn push(&mut self, task: TaskData, now: f64) {
+        self.tasks.push(DeletedTask {
+            deleted_at: now,
+            task,
+        });
+        self.purge(now);
+    }
+
+    /// Removes the task with `id` to restore it.
+    pub fn take(&mut self, id: Uuid) -> Option<TaskData> {
+        let i = self
+            .tasks
+            .iter()
+            .position(|deleted| deleted.task.id() == id)?;
+        Some(self.tasks.remove(i).task)
+    }
+
+    /// Drops the tasks that are back on the board of `db`, e.g. after an undo.
+    /// Returns whether there were any.
+    pub fn remove_restored(&mut self, db: &DataBase) -> bool {
+        let len = self.tasks.len();
+        self.tasks
+            .retain(|deleted| db.get_by_id(deleted.task.id()).is_none());
+        self.tasks.len() != len
+    }
+
+    pub fn clear(&mut self) {
+        self.tasks.clear();
+    }
+
+    /// Tasks from the most recently deleted to the oldest.
+    pub fn tasks(&self) -> impl Iterator<Item = &DeletedTask> {
+        self.tasks.iter().rev()
+    }
+
+    pub fn retention_days(&self) -> u32 {
+        self.retention_days
+    }
+
+    /// Keeps deleted tasks for `days` from `now` on, dropping the older ones.
+    pub fn set_retention_days(&mut self, days: u32, now: f64) {
+        self.retention_days = days;
+        self.purge(now);
+    }
+
+    /// Drops the tasks deleted more than `retention_days` before `now`.
+    fn purge(&mut self, now: f64) {
+        let oldest = now - f64::from(self.retention_days) * DAY_MS;
+        self.tasks.retain(|deleted| deleted.deleted_at >= oldest);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    fn task(value: &str) -> TaskData {
+        TaskData::new(TaskRank::Secondary, value)
+    }
+
+    fn values(trash: &Trash) -> Vec<&str> {
+        trash
+            .tasks()
+            .map(|deleted| deleted.task.value.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn deleted_tasks_are_saved_and_taken_back() {
+        set_storage(Rc::new(MemoryBackend::default()));
+        let board = Uuid::new_v4();
+        let mut trash = Trash::load_from_local_storage(board, 0.0);
+        let first = task("first");
+        let id = first.id();
+        trash.push(first, DAY_MS);
+        trash.push(task("second"), 2.0 * DAY_MS);
+        trash.save_to_local_storage();
+
+        let mut loaded = Trash::load_from_local_storage(board, 2.0 * DAY_MS);
+        assert_eq!(loaded, trash);
+        assert_eq!(values(&loaded), ["second", "first"]);
+
+        let taken = loaded.take(id).unwrap();
+        assert_eq!(taken.value, "first");
+        assert_eq!(taken.rank, TaskRank::Secondary);
+        assert!(loaded.take(id).is_none());
+        assert_eq!(values(&loaded), ["second"]);
+    }
+
+    #[test]
+    fn tasks_back_on_the_board_are_removed() {
+        let mut db = DataBase::default();
+        let mut trash = Trash::default();
+        let deleted = task("deleted");
+        trash.push(deleted.clone(), 1.0);
+        trash.push(task("still deleted"), 1.0);
+        assert!(!trash.remove_restored(&db));
+
+        db.restore_task(&deleted, deleted.rank, 2.0);
+        assert!(trash.remove_restored(&db));
+        assert_eq!(values(&trash), ["still deleted"]);
+    }
+
+    #[test]
+    fn tasks_older_than_the_retention_are_purged() {
+        set_storage(Rc::new(MemoryBackend::default()));
+        let board = Uuid::new_v4();
+        let mut trash = Trash::load_from_local_storage(board, 0.0);
+        trash.push(task("old"), 0.0);
+        trash.push(task("recent"), 20.0 * DAY_MS);
+        trash.save_to_local_storage();
+
+        // pushing purges
+        trash.push(task("new"), 30.5 * DAY_MS);
+        assert_eq!(values(&trash), ["new", "recent"]);
+
+        // so does loading
+        let loaded = Trash::load_from_local_storage(board, 51.0 * DAY_MS);
+        assert!(values(&loaded).is_empty());
+
+        // and a shorter retention
+        trash.set_retention_days(7, 30.5 * DAY_MS);
+        assert_eq!(values(&trash), ["new"]);
+        assert_eq!(trash.retention_days(), 7);
+        trash.save_to_local_storage();
+        let loaded = Trash::load_from_local_storage(board, 30.5 * DAY_MS);
+        assert_eq!(loaded.retention_days(), 7);
+    }
+}
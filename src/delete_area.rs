@@ -0,0 +1,26 @@
+use crate::prelude::*;
+use yew::prelude::*;
+use yewdux::prelude::*;
+
+/// Deletes the card dropped onto it. Only shown while a card is dragged; the
+/// drop itself is handled by `DraggableArea`.
+#[function_component(DeleteArea)]
+pub fn delete_area() -> Html {
+    let card_id = use_store::<DraggingCardId>().0.id;
+    let target = use_store::<DraggingDropTarget>().0.target;
+
+    let mut class = classes!("delete-area");
+    if card_id.is_none() {
+        class.push("invisible");
+    }
+    if target == Some(DropTarget::Delete) {
+        class.push("drop-hover");
+    }
+
+    html! {
+        <div class={class} data-drop-target={DropTarget::Delete.name()}>
+        <svg xmlns="http://www.w3.org/2000/svg" width="24" height="24" fill="currentColor" class="bi bi-trash" viewBox="0 0 16 16"><path d="M5.5 5.5A.5.5 0 0 1 6 6v6a.5.5 0 0 1-1 0V6a.5.5 0 0 1 .5-.5zm2.5 0a.5.5 0 0 1 .5.5v6a.5.5 0 0 1-1 0V6a.5.5 0 0 1 .5-.5zm3 .5a.5.5 0 0 0-1 0v6a.5.5 0 0 0 1 0V6z"/><path fill-rule="evenodd" d="M14.5 3a1 1 0 0 1-1 1H13v9a2 2 0 0 1-2 2H5a2 2 0 0 1-2-2V4h-.5a1 1 0 0 1-1-1V2a1 1 0 0 1 1-1H6a1 1 0 0 1 1-1h2a1 1 0 0 1 1 1h3.5a1 1 0 0 1 1 1v1zM4.118 4 4 4.059V13a1 1 0 0 0 1 1h6a1 1 0 0 0 1-1V4.059L11.882 4H4.118zM2.5 3V2h11v1h-11z"/></svg>
+        <div>{"削除"}</div>
+        </div>
+    }
+}
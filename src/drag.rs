@@ -1,104 +1,367 @@
 use crate::prelude::*;
+use uuid::Uuid;
 use wasm_bindgen::closure::Closure;
 use wasm_bindgen::JsCast;
+use web_sys::{AddEventListenerOptions, Element, PointerEvent, TouchEvent};
 use yew::prelude::*;
 use yewdux::prelude::*;
 
+/// How far outside a column a touch still drops into it, as the finger hides
+/// the exact point.
+const TOUCH_HIT_SLOP_PX: f64 = 24.0;
+
+/// How far past the middle of a card the pointer has to go before the blank
+/// card moves to its other side, so the blank doesn't flicker at the boundary.
+const DROP_HYSTERESIS_PX: f64 = 8.0;
+
+#[derive(Properties, PartialEq)]
+pub struct DraggableAreaProps {
+    pub onedit: Callback<TaskEvent>,
+    /// Burns the card dropped onto the burn button.
+    pub onburn: Callback<Uuid>,
+}
+
+/// Follows the pointer that picked up a card and drops the card where it is
+/// released. Cards start the drag; the pointer is then captured here so the
+/// moves keep coming after the card leaves its list.
 pub struct DraggableArea {
+    node_ref: NodeRef,
+    dispatch_pointer: Dispatch<DraggingPointerId>,
     dispatch_id: Dispatch<DraggingCardId>,
     dispatch_initial_pos: Dispatch<DraggingInitialPos>,
     dispatch_mouse_pos: Dispatch<DraggingMousePos>,
+    dispatch_index: Dispatch<DraggingPositionIndex>,
+    dispatch_rank: Dispatch<DraggingPositionRank>,
+    dispatch_drop_target: Dispatch<DraggingDropTarget>,
+
+    on_pointer_move: Option<Closure<dyn Fn(PointerEvent)>>,
+    on_pointer_up: Option<Closure<dyn Fn(PointerEvent)>>,
+    on_pointer_cancel: Option<Closure<dyn Fn(PointerEvent)>>,
+    on_touch_move: Option<Closure<dyn Fn(TouchEvent)>>,
+}
 
-    on_mouse_move: Option<Closure<dyn Fn(MouseEvent)>>,
-    on_mouse_up: Option<Closure<dyn Fn(MouseEvent)>>,
+pub enum DraggableAreaMsg {
+    ChangePointer,
+    PointerMove(PointerEvent),
+    PointerUp(PointerEvent),
+    PointerCancel(PointerEvent),
 }
 
 impl Component for DraggableArea {
-    type Message = ();
-    type Properties = ();
+    type Message = DraggableAreaMsg;
+    type Properties = DraggableAreaProps;
 
-    fn create(_ctx: &Context<Self>) -> Self {
+    fn create(ctx: &Context<Self>) -> Self {
+        let on_change_pointer = ctx
+            .link()
+            .callback(move |_| DraggableAreaMsg::ChangePointer);
         Self {
+            node_ref: NodeRef::default(),
+            dispatch_pointer: Dispatch::subscribe(on_change_pointer),
             dispatch_id: Dispatch::subscribe(Callback::noop()),
             dispatch_initial_pos: Dispatch::subscribe(Callback::noop()),
             dispatch_mouse_pos: Dispatch::subscribe(Callback::noop()),
-            on_mouse_move: None,
-            on_mouse_up: None,
+            dispatch_index: Dispatch::subscribe(Callback::noop()),
+            dispatch_rank: Dispatch::subscribe(Callback::noop()),
+            dispatch_drop_target: Dispatch::subscribe(Callback::noop()),
+            on_pointer_move: None,
+            on_pointer_up: None,
+            on_pointer_cancel: None,
+            on_touch_move: None,
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        let pointer_id = self.dispatch_pointer.get().id;
+
+        use DraggableAreaMsg::*;
+        match msg {
+            ChangePointer => {
+                if let (Some(pointer_id), Some(element)) =
+                    (pointer_id, self.node_ref.cast::<Element>())
+                {
+                    // fails once the pointer is already up, which ends the drag anyway
+                    let _ = element.set_pointer_capture(pointer_id);
+                }
+            }
+            PointerMove(e) => {
+                if pointer_id != Some(e.pointer_id()) {
+                    return false;
+                }
+                let pos = (e.page_x(), e.page_y());
+                self.dispatch_mouse_pos
+                    .reduce(|_| DraggingMousePos { pos: Some(pos) });
+
+                let (x, y) = (f64::from(e.client_x()), f64::from(e.client_y()));
+                let slop = if e.pointer_type() == "mouse" {
+                    0.0
+                } else {
+                    TOUCH_HIT_SLOP_PX
+                };
+                let target = drop_target_at(x, y, slop);
+                if self.dispatch_drop_target.get().target != target {
+                    self.dispatch_drop_target
+                        .reduce(|_| DraggingDropTarget { target });
+                }
+                // a drop target lies over the columns
+                let rank = match target {
+                    Some(_) => None,
+                    None => rank_at(x, y, slop),
+                };
+                if self.dispatch_rank.get().rank != rank {
+                    self.dispatch_rank.reduce(|_| DraggingPositionRank { rank });
+                    self.dispatch_index
+                        .reduce(|_| DraggingPositionIndex { index: None });
+                }
+                let rank = match rank {
+                    Some(rank) => rank,
+                    None => return false,
+                };
+
+                follow_drop_index(rank, y);
+            }
+            PointerUp(e) => {
+                if pointer_id != Some(e.pointer_id()) {
+                    return false;
+                }
+                let id = self.dispatch_id.get().id;
+                let rank = self.dispatch_rank.get().rank;
+                let index = self.dispatch_index.get().index;
+                let target = self.dispatch_drop_target.get().target;
+                log::debug!(
+                    "[drag] drop, rank: {:?}, index: {:?}, target: {:?}",
+                    rank,
+                    index,
+                    target
+                );
+                match (id, target, rank) {
+                    (Some(id), Some(DropTarget::Delete), _) => ctx
+                        .props()
+                        .onedit
+                        .emit(TaskEvent::Delete(TaskDeleteData { id })),
+                    (Some(id), Some(DropTarget::Burn), _) => ctx.props().onburn.emit(id),
+                    (Some(id), None, Some(rank)) => ctx
+                        .props()
+                        .onedit
+                        .emit(TaskEvent::Move(TaskMoveData { id, rank, index })),
+                    _ => {}
+                }
+                self.end();
+            }
+            PointerCancel(e) => {
+                if pointer_id != Some(e.pointer_id()) {
+                    return false;
+                }
+                log::debug!("[drag] cancel");
+                self.end();
+            }
         }
+        false
     }
 
-    fn rendered(&mut self, _ctx: &Context<Self>, first_render: bool) {
+    fn rendered(&mut self, ctx: &Context<Self>, first_render: bool) {
         if !first_render {
             return;
         }
 
-        let on_mouse_move = {
-            self.dispatch_mouse_pos
-                .reduce_mut_callback_with(move |s, e: MouseEvent| {
-                    // log::debug!("[document] mousemove");
-                    s.pos = Some((e.page_x(), e.page_y()));
-                })
+        let on_pointer_move = {
+            let link = ctx.link().clone();
+            Closure::<dyn Fn(PointerEvent)>::wrap(Box::new(move |e| {
+                link.send_message(DraggableAreaMsg::PointerMove(e))
+            }))
         };
-
-        let on_mouse_up_state = {
-            self.dispatch_initial_pos.reduce_mut_callback(move |s| {
-                s.pos = None;
-            })
+        let on_pointer_up = {
+            let link = ctx.link().clone();
+            Closure::<dyn Fn(PointerEvent)>::wrap(Box::new(move |e| {
+                link.send_message(DraggableAreaMsg::PointerUp(e))
+            }))
         };
-
-        let on_mouse_up_id = {
-            self.dispatch_id.reduce_mut_callback(move |s| {
-                s.id = None;
-            })
+        let on_pointer_cancel = {
+            let link = ctx.link().clone();
+            Closure::<dyn Fn(PointerEvent)>::wrap(Box::new(move |e| {
+                link.send_message(DraggableAreaMsg::PointerCancel(e))
+            }))
+        };
+        // a touch would scroll the page instead of moving the card
+        let on_touch_move = {
+            let dispatch_id = self.dispatch_id.clone();
+            Closure::<dyn Fn(TouchEvent)>::wrap(Box::new(move |e: TouchEvent| {
+                if dispatch_id.get().id.is_some() && e.cancelable() {
+                    e.prevent_default();
+                }
+            }))
         };
 
-        let on_mouse_move =
-            Closure::<dyn Fn(MouseEvent)>::wrap(Box::new(move |e| on_mouse_move.emit(e)));
-        let on_mouse_up = Closure::<dyn Fn(MouseEvent)>::wrap(Box::new(move |e| {
-            on_mouse_up_state.emit(e.clone());
-            on_mouse_up_id.emit(e);
-        }));
-
-        let document = web_sys::window()
-            .expect("window")
-            .document()
-            .expect("document");
-        document
-            .add_event_listener_with_callback("mousemove", on_mouse_move.as_ref().unchecked_ref())
-            .unwrap();
+        let document = gloo::utils::document();
+        for (event, listener) in [
+            ("pointermove", on_pointer_move.as_ref()),
+            ("pointerup", on_pointer_up.as_ref()),
+            ("pointercancel", on_pointer_cancel.as_ref()),
+        ] {
+            document
+                .add_event_listener_with_callback(event, listener.unchecked_ref())
+                .unwrap();
+        }
         document
-            .add_event_listener_with_callback("mouseup", on_mouse_up.as_ref().unchecked_ref())
+            .add_event_listener_with_callback_and_add_event_listener_options(
+                "touchmove",
+                on_touch_move.as_ref().unchecked_ref(),
+                AddEventListenerOptions::new().passive(false),
+            )
             .unwrap();
 
-        self.on_mouse_move = Some(on_mouse_move);
-        self.on_mouse_up = Some(on_mouse_up);
+        self.on_pointer_move = Some(on_pointer_move);
+        self.on_pointer_up = Some(on_pointer_up);
+        self.on_pointer_cancel = Some(on_pointer_cancel);
+        self.on_touch_move = Some(on_touch_move);
     }
 
     fn destroy(&mut self, _ctx: &Context<Self>) {
-        let document = web_sys::window()
-            .expect("window")
-            .document()
-            .expect("document");
-        if let Some(on_mouse_move) = self.on_mouse_move.take() {
-            document
-                .add_event_listener_with_callback(
-                    "mousemove",
-                    on_mouse_move.as_ref().unchecked_ref(),
-                )
-                .unwrap();
+        let document = gloo::utils::document();
+        for (event, listener) in [
+            ("pointermove", self.on_pointer_move.take()),
+            ("pointerup", self.on_pointer_up.take()),
+            ("pointercancel", self.on_pointer_cancel.take()),
+        ] {
+            if let Some(listener) = listener {
+                document
+                    .remove_event_listener_with_callback(event, listener.as_ref().unchecked_ref())
+                    .unwrap();
+            }
         }
-        if let Some(on_mouse_up) = self.on_mouse_up.take() {
+        if let Some(on_touch_move) = self.on_touch_move.take() {
             document
-                .add_event_listener_with_callback("mouseup", on_mouse_up.as_ref().unchecked_ref())
+                .remove_event_listener_with_callback(
+                    "touchmove",
+                    on_touch_move.as_ref().unchecked_ref(),
+                )
                 .unwrap();
         }
     }
 
     fn view(&self, _ctx: &Context<Self>) -> Html {
         html! {
-            <div class="draggable-area">
+            <div class="draggable-area" ref={self.node_ref.clone()}>
                 <DraggingCard/>
             </div>
         }
     }
 }
+
+impl DraggableArea {
+    fn end(&self) {
+        self.dispatch_pointer
+            .reduce(|_| DraggingPointerId { id: None });
+        self.dispatch_initial_pos
+            .reduce(|_| DraggingInitialPos { pos: None });
+        self.dispatch_id.reduce(|_| DraggingCardId { id: None });
+        self.dispatch_index
+            .reduce(|_| DraggingPositionIndex { index: None });
+        self.dispatch_rank
+            .reduce(|_| DraggingPositionRank { rank: None });
+        self.dispatch_drop_target
+            .reduce(|_| DraggingDropTarget { target: None });
+    }
+}
+
+/// The drop target nearest to the point in client coordinates, if it is no
+/// farther than `slop`.
+fn drop_target_at(x: f64, y: f64, slop: f64) -> Option<DropTarget> {
+    nearest_at(x, y, slop, "[data-drop-target]", |element| {
+        DropTarget::from_name(&element.get_attribute("data-drop-target")?)
+    })
+}
+
+/// The column nearest to the point in client coordinates, if it is no farther
+/// than `slop`.
+fn rank_at(x: f64, y: f64, slop: f64) -> Option<TaskRank> {
+    nearest_at(x, y, slop, ".task-list", |element| {
+        element.get_attribute("data-rank")?.parse().ok()
+    })
+}
+
+/// The value `read` finds on the element matching `selectors` nearest to the
+/// point, if it is no farther than `slop`.
+fn nearest_at<T>(
+    x: f64,
+    y: f64,
+    slop: f64,
+    selectors: &str,
+    read: impl Fn(&Element) -> Option<T>,
+) -> Option<T> {
+    let elements = gloo::utils::document().query_selector_all(selectors).ok()?;
+    let mut nearest: Option<(f64, T)> = None;
+    for i in 0..elements.length() {
+        let element = match elements
+            .item(i)
+            .and_then(|node| node.dyn_into::<Element>().ok())
+        {
+            Some(element) => element,
+            None => continue,
+        };
+        let value = match read(&element) {
+            Some(value) => value,
+            None => continue,
+        };
+        let rect = element.get_bounding_client_rect();
+        let dx = (rect.left() - x).max(x - rect.right()).max(0.0);
+        let dy = (rect.top() - y).max(y - rect.bottom()).max(0.0);
+        let distance = dx.hypot(dy);
+        if distance <= slop
+            && nearest
+                .as_ref()
+                .is_none_or(|(nearest, _)| distance < *nearest)
+        {
+            nearest = Some((distance, value));
+        }
+    }
+    nearest.map(|(_, value)| value)
+}
+
+/// Moves the `BlankCard` in the column of `rank` to where the pointer at `y`
+/// in client coordinates would drop the card.
+pub(crate) fn follow_drop_index(rank: TaskRank, y: f64) {
+    let dispatch_index = Dispatch::<DraggingPositionIndex>::new();
+    let current = dispatch_index.get().index;
+    let index = drop_index(rank, y, current);
+    if index.is_some() && current != index {
+        dispatch_index.reduce(|_| DraggingPositionIndex { index });
+    }
+}
+
+/// Where the dragged card goes in the column of `rank` for the pointer at `y`
+/// in client coordinates, while the `BlankCard` is shown at `current`, or at
+/// the end for `None`. A card changes sides with the blank once the pointer is
+/// `DROP_HYSTERESIS_PX` past its middle.
+fn drop_index(rank: TaskRank, y: f64, current: Option<usize>) -> Option<usize> {
+    let list = gloo::utils::document()
+        .query_selector(&format!(".task-list[data-rank=\"{}\"]", rank))
+        .ok()??;
+    // the dragged card isn't in the list, so these are numbered as in the drop
+    let cards = list.query_selector_all(".card[data-index]").ok()?;
+    let count = cards.length() as usize;
+    let current = current.map_or(count, |current| current.min(count));
+
+    let mut index = 0;
+    while index < count {
+        let card = match cards
+            .item(index as u32)
+            .and_then(|node| node.dyn_into::<Element>().ok())
+        {
+            Some(card) => card,
+            None => break,
+        };
+        let rect = card.get_bounding_client_rect();
+        let middle = rect.top() + rect.height() / 2.0;
+        let threshold = if index < current {
+            middle - DROP_HYSTERESIS_PX
+        } else {
+            middle + DROP_HYSTERESIS_PX
+        };
+        if y < threshold {
+            break;
+        }
+        index += 1;
+    }
+    Some(index)
+}
@@ -0,0 +1,87 @@
+use crate::prelude::*;
+use std::rc::Rc;
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct ImportDialogProps {
+    pub preview: Rc<ImportPreview>,
+    pub onimport: Callback<ImportMode>,
+    pub onclose: Callback<MouseEvent>,
+}
+
+#[function_component(ImportDialog)]
+pub fn import_dialog(props: &ImportDialogProps) -> Html {
+    let mode = use_state(|| ImportMode::Merge);
+    let changes = props.preview.changes(*mode);
+
+    let mode_button = |value: ImportMode, label: &str| {
+        let onclick = {
+            let mode = mode.clone();
+            Callback::from(move |_: MouseEvent| mode.set(value))
+        };
+        let class = classes!("import-dialog-mode", (*mode == value).then_some("selected"));
+        let count = props.preview.changes(value).len();
+        html! {
+            <button {class} {onclick}>{ format!("{} ({}件)", label, count) }</button>
+        }
+    };
+
+    let on_import = {
+        let onimport = props.onimport.clone();
+        let mode = *mode;
+        Callback::from(move |_: MouseEvent| onimport.emit(mode))
+    };
+
+    html! {
+        <div class="import-dialog-backdrop">
+            <div class="import-dialog">
+                <div class="import-dialog-header">
+                    <div>{ format!("「{}」を読み込む", props.preview.file_name) }</div>
+                    <button class="import-dialog-close" onclick={props.onclose.clone()}>{"×"}</button>
+                </div>
+                <div class="import-dialog-modes">
+                    { mode_button(ImportMode::Merge, "マージ") }
+                    { mode_button(ImportMode::Replace, "置き換え") }
+                </div>
+                <div class="import-dialog-changes">
+                    if changes.is_empty() {
+                        <div class="import-dialog-empty">{"変更はありません"}</div>
+                    }
+                    { for changes.iter().map(task_change) }
+                </div>
+                <div class="import-dialog-footer">
+                    <button onclick={props.onclose.clone()}>{"キャンセル"}</button>
+                    <button onclick={on_import} disabled={changes.is_empty()}>{"読み込む"}</button>
+                </div>
+            </div>
+        </div>
+    }
+}
+
+fn task_change(change: &TaskChange) -> Html {
+    let (label, task) = match change {
+        TaskChange::Added(task) => ("追加", task),
+        TaskChange::Removed(task) => ("削除", task),
+        TaskChange::Changed { before, after } => {
+            let label = if before.value != after.value {
+                "編集"
+            } else if before.done != after.done && after.done {
+                "完了"
+            } else if before.done != after.done {
+                "未完了"
+            } else {
+                "移動"
+            };
+            (label, &**after)
+        }
+    };
+
+    html! {
+        <div class="import-dialog-change">
+            <div class="import-dialog-change-label">
+                { format!("{} ・ {}", label, task.rank) }
+            </div>
+            <p class="card">{ task_value_as_html(&task.value, task.done) }</p>
+        </div>
+    }
+}
@@ -1,31 +1,46 @@
+use crate::drag::follow_drop_index;
 use crate::prelude::*;
+use gloo::timers::callback::Interval;
 use std::rc::Rc;
-use wasm_bindgen::JsCast;
-use web_sys::{EventTarget, Node};
+use web_sys::Element;
 use yew::prelude::*;
 use yewdux::prelude::*;
 
+/// Distance from the top or bottom of a list within which a dragged card
+/// scrolls it.
+const AUTO_SCROLL_EDGE_PX: f64 = 48.0;
+
+/// Scroll per step with the pointer right at the edge.
+const AUTO_SCROLL_MAX_PX: f64 = 16.0;
+
+const AUTO_SCROLL_INTERVAL_MS: u32 = 16;
+
 #[derive(Properties, PartialEq)]
 pub struct TaskListProps {
     pub rank: TaskRank,
     pub onedit: Callback<TaskEvent>,
+    /// Events applied together, as one change to undo.
+    pub onbatch: Callback<Vec<TaskEvent>>,
 }
 
 pub struct TaskList {
-    node_ref: NodeRef,
+    /// Whether the `BlankCard` was shown in the last view.
+    blank_shown: bool,
+    scroller_ref: NodeRef,
+    /// Scrolls while a card is dragged near an edge.
+    auto_scroll: Option<Interval>,
     dispatch_drag_rank: Dispatch<DraggingPositionRank>,
+    dispatch_drag_mouse_pos: Dispatch<DraggingMousePos>,
     dispatch_drag_index: Dispatch<DraggingPositionIndex>,
     dispatch_drag_card_id: Dispatch<DraggingCardId>,
 }
 
 pub enum TaskListMsg {
-    MouseUp,
-    MouseEnter(MouseEvent),
-    MouseLeave(MouseEvent),
-
-    ChangeDragRank,
-    ChangeDragIndex,
+    /// The dragged card moved to another rank or index.
+    ChangeDragTarget,
     ChangeDragCardId,
+    MoveDragPointer,
+    AutoScroll,
 }
 
 impl Component for TaskList {
@@ -33,90 +48,52 @@ impl Component for TaskList {
     type Properties = TaskListProps;
 
     fn create(ctx: &Context<Self>) -> Self {
-        let on_change_drag_rank = ctx.link().callback(move |_| TaskListMsg::ChangeDragRank);
-        let on_change_drag_index = ctx.link().callback(move |_| TaskListMsg::ChangeDragIndex);
+        let on_change_drag_rank = ctx.link().callback(move |_| TaskListMsg::ChangeDragTarget);
+        let on_change_drag_index = ctx.link().callback(move |_| TaskListMsg::ChangeDragTarget);
         let on_change_drag_card_id = ctx.link().callback(move |_| TaskListMsg::ChangeDragCardId);
+        let on_move_drag_pointer = ctx.link().callback(move |_| TaskListMsg::MoveDragPointer);
         Self {
-            node_ref: NodeRef::default(),
+            blank_shown: false,
+            scroller_ref: NodeRef::default(),
+            auto_scroll: None,
             dispatch_drag_rank: Dispatch::subscribe(on_change_drag_rank),
+            dispatch_drag_mouse_pos: Dispatch::subscribe(on_move_drag_pointer),
             dispatch_drag_index: Dispatch::subscribe(on_change_drag_index),
             dispatch_drag_card_id: Dispatch::subscribe(on_change_drag_card_id),
         }
     }
 
     fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
-        let props = ctx.props();
-        let drag_onto_this = self.drag_onto_this(ctx);
-
-        let opt_id = self.dispatch_drag_card_id.get().id;
-        let opt_rank = self.dispatch_drag_rank.get().rank;
-        let opt_index = self.dispatch_drag_index.get().index;
-
         use TaskListMsg::*;
         match msg {
-            MouseUp => {
-                log::debug!("[list] on_mouse_up, rank: {:?}", props.rank);
-                if let Some(id) = opt_id {
-                    log::debug!("[list] edit, task-id: {}", id);
-
-                    self.dispatch_drag_card_id
-                        .reduce(|_| DraggingCardId { id: None });
-                    self.dispatch_drag_index
-                        .reduce(|_| DraggingPositionIndex { index: None });
-                    props.onedit.emit(TaskEvent::Move(TaskMoveData {
-                        id,
-                        rank: props.rank,
-                        index: opt_index,
-                    }));
-
-                    return true;
-                }
-                return false;
+            // moves the blank card, or takes it away
+            ChangeDragTarget => {
+                self.start_or_stop_auto_scroll(ctx);
+                self.drag_onto_this(ctx) || self.blank_shown
             }
-            MouseEnter(e) => {
-                if let Some(target) = e.target() {
-                    if !eq_target_node(target, &self.node_ref) {
-                        return false;
-                    }
-                }
-                if opt_rank == Some(props.rank) {
-                    return false;
-                }
-
-                // log::debug!("[list] on_mouse_enter, rank: {:?}, drag_rank: {:?} -> {:?}", props.rank, opt_rank, Some(props.rank));
-
-                let rank = props.rank;
-                self.dispatch_drag_rank
-                    .reduce(|_| DraggingPositionRank { rank: Some(rank) });
-                return drag_onto_this;
+            // the dragged card leaves or comes back to its list
+            ChangeDragCardId => {
+                self.start_or_stop_auto_scroll(ctx);
+                true
+            }
+            MoveDragPointer => {
+                self.start_or_stop_auto_scroll(ctx);
+                false
             }
-            MouseLeave(e) => {
-                if let Some(target) = e.target() {
-                    if !eq_target_node(target, &self.node_ref) {
+            AutoScroll => {
+                let (speed, y) = match self.auto_scroll_speed(ctx) {
+                    Some((speed, y)) if speed != 0 => (speed, y),
+                    _ => {
+                        self.auto_scroll = None;
                         return false;
                     }
+                };
+                if let Some(scroller) = self.scroller_ref.cast::<Element>() {
+                    scroller.set_scroll_top(scroller.scroll_top() + speed);
                 }
-                if opt_rank == None {
-                    return false;
-                }
-
-                // log::debug!("[list] on_mouse_leave, rank: {:?}, drag_rank: {:?} -> {:?}", props.rank, opt_rank, None as Option<TaskRank>);
-
-                self.dispatch_drag_rank
-                    .reduce(|_| DraggingPositionRank { rank: None });
-                self.dispatch_drag_index
-                    .reduce(|_| DraggingPositionIndex { index: None });
-                return drag_onto_this;
-            }
-
-            ChangeDragRank => {
-                return drag_onto_this;
-            }
-            ChangeDragIndex => {
-                return drag_onto_this;
-            }
-            ChangeDragCardId => {
-                return opt_rank == Some(props.rank);
+                // the cards move under the pointer
+                follow_drop_index(ctx.props().rank, y);
+                false
             }
         }
     }
@@ -161,18 +138,9 @@ impl Component for TaskList {
                 (tasks, vec![])
             };
 
-        let on_mouse_up = ctx.link().callback(move |_| TaskListMsg::MouseUp);
-        let on_mouse_enter = ctx.link().callback(move |e| TaskListMsg::MouseEnter(e));
-        let on_mouse_leave = ctx.link().callback(move |e| TaskListMsg::MouseLeave(e));
-
         html! {
-            <div class="task-list"
-                ref={self.node_ref.clone()}
-                onmouseup={on_mouse_up}
-                onmouseenter={on_mouse_enter}
-                onmouseleave={on_mouse_leave}
-            >
-            <div class="task-scroller">
+            <div class="task-list" data-rank={props.rank.to_string()}>
+            <div class="task-scroller" ref={self.scroller_ref.clone()}>
             <div class="task-scroller-inner">
 
                 { for before_blank.iter().map(|(task, i)| html!(
@@ -191,13 +159,20 @@ impl Component for TaskList {
                     />
                 )) }
 
-                <AddCard rank={props.rank} onedit={props.onedit.clone()}/>
+                <AddCard rank={props.rank}
+                    onedit={props.onedit.clone()}
+                    onbatch={props.onbatch.clone()}
+                />
 
             </div>
             </div>
             </div>
         }
     }
+
+    fn rendered(&mut self, ctx: &Context<Self>, _first_render: bool) {
+        self.blank_shown = self.drag_onto_this(ctx);
+    }
 }
 
 impl TaskList {
@@ -206,16 +181,41 @@ impl TaskList {
         let opt_rank = self.dispatch_drag_rank.get().rank;
         opt_id.is_some() && opt_rank.is_some() && opt_rank == Some(ctx.props().rank)
     }
-}
 
-fn eq_target_node(target: EventTarget, node_ref: &NodeRef) -> bool {
-    if let Ok(target) = target.dyn_into::<Node>() {
-        if let Some(node) = node_ref.get() {
-            if target == node {
-                return true;
-            }
+    fn start_or_stop_auto_scroll(&mut self, ctx: &Context<Self>) {
+        let scrolling = matches!(self.auto_scroll_speed(ctx), Some((speed, _)) if speed != 0);
+        if !scrolling {
+            self.auto_scroll = None;
+        } else if self.auto_scroll.is_none() {
+            let link = ctx.link().clone();
+            self.auto_scroll = Some(Interval::new(AUTO_SCROLL_INTERVAL_MS, move || {
+                link.send_message(TaskListMsg::AutoScroll)
+            }));
         }
     }
 
-    false
+    /// Pixels to scroll by per step for the card dragged over this list,
+    /// negative for up, growing as the pointer gets closer to the edge. Also
+    /// returns the pointer position in client coordinates.
+    fn auto_scroll_speed(&self, ctx: &Context<Self>) -> Option<(i32, f64)> {
+        if !self.drag_onto_this(ctx) {
+            return None;
+        }
+        let (_, page_y) = self.dispatch_drag_mouse_pos.get().pos?;
+        let scroller = self.scroller_ref.cast::<Element>()?;
+        let y = f64::from(page_y) - gloo::utils::window().scroll_y().unwrap_or_default();
+
+        let rect = scroller.get_bounding_client_rect();
+        // short lists keep room in the middle to drop without scrolling
+        let edge = AUTO_SCROLL_EDGE_PX.min(rect.height() / 3.0);
+        let depth = if y < rect.top() + edge {
+            y - (rect.top() + edge)
+        } else if y > rect.bottom() - edge {
+            y - (rect.bottom() - edge)
+        } else {
+            0.0
+        };
+        let speed = (depth / edge).clamp(-1.0, 1.0) * AUTO_SCROLL_MAX_PX;
+        Some((speed.round() as i32, y))
+    }
 }
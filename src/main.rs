@@ -1,15 +1,22 @@
 mod app;
+mod board_switcher;
+mod burn_history;
 mod button;
 mod card;
 mod data;
 mod debug;
+mod delete_area;
 mod drag;
-mod event;
+mod import_dialog;
 mod list;
 mod prelude;
+mod route;
 mod state;
+mod toast;
+mod trash_bin;
 
 use app::App;
+use data::{open_storage, set_storage};
 use log::Level;
 
 fn main() {
@@ -19,5 +26,8 @@ fn main() {
         Level::Warn
     };
     wasm_logger::init(wasm_logger::Config::new(log_level));
-    yew::start_app::<App>();
+    wasm_bindgen_futures::spawn_local(async {
+        set_storage(open_storage().await);
+        yew::start_app::<App>();
+    });
 }
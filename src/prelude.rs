@@ -1 +1,5 @@
-pub use crate::{button::*, card::*, data::*, drag::*, event::*, list::*, state::*};
+pub use crate::{
+    board_switcher::*, burn_history::*, button::*, card::*, data::*, delete_area::*, drag::*,
+    import_dialog::*, list::*, route::*, state::*, toast::*, trash_bin::*,
+};
+pub use burner_list_core::*;
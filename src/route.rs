@@ -0,0 +1,19 @@
+use uuid::Uuid;
+
+/// Reads the board id from a `#/board/<id>` location hash.
+pub fn board_from_location() -> Option<Uuid> {
+    let hash = web_sys::window()?.location().hash().ok()?;
+    let id = hash.strip_prefix("#/board/")?;
+    Uuid::parse_str(id).ok()
+}
+
+pub fn set_location_board(id: Uuid) {
+    if board_from_location() == Some(id) {
+        return;
+    }
+
+    let location = web_sys::window().expect("window").location();
+    if let Err(e) = location.set_hash(&format!("/board/{}", id)) {
+        log::error!("[route] {:?}", e);
+    }
+}
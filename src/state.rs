@@ -26,3 +26,52 @@ pub struct DraggingInitialPos {
 pub struct DraggingMousePos {
     pub pos: Option<(i32, i32)>,
 }
+
+/// Something other than a column that takes the dragged card.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DropTarget {
+    /// Deletes the card into the trash.
+    Delete,
+    /// Burns the card alone.
+    Burn,
+}
+
+impl DropTarget {
+    /// Marks an element as a drop target with `data-drop-target`.
+    pub fn name(self) -> &'static str {
+        match self {
+            DropTarget::Delete => "delete",
+            DropTarget::Burn => "burn",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        [DropTarget::Delete, DropTarget::Burn]
+            .into_iter()
+            .find(|target| target.name() == name)
+    }
+}
+
+/// The drop target under the pointer, in place of a column.
+#[derive(Debug, Default, Clone, PartialEq, Store)]
+pub struct DraggingDropTarget {
+    pub target: Option<DropTarget>,
+}
+
+/// Pointer that picked up the dragged card.
+#[derive(Debug, Default, Clone, PartialEq, Store)]
+pub struct DraggingPointerId {
+    pub id: Option<i32>,
+}
+
+/// Card that has the keyboard focus, or is about to get it once rendered.
+#[derive(Debug, Default, Clone, PartialEq, Store)]
+pub struct FocusedCardId {
+    pub id: Option<Uuid>,
+}
+
+/// Rank whose `AddCard` is opened from the keyboard.
+#[derive(Debug, Default, Clone, PartialEq, Store)]
+pub struct AddingRank {
+    pub rank: Option<TaskRank>,
+}
@@ -0,0 +1,20 @@
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct UndoToastProps {
+    pub message: String,
+    /// `None` for a message about something that cannot be undone.
+    pub onundo: Option<Callback<MouseEvent>>,
+}
+
+#[function_component(UndoToast)]
+pub fn undo_toast(props: &UndoToastProps) -> Html {
+    html! {
+        <div class={classes!("toast", props.onundo.is_none().then_some("toast-error"))}>
+            <span>{ props.message.clone() }</span>
+            if let Some(onundo) = &props.onundo {
+                <button class="toast-button" onclick={onundo.clone()}>{"元に戻す"}</button>
+            }
+        </div>
+    }
+}
@@ -0,0 +1,62 @@
+use crate::prelude::*;
+use std::rc::Rc;
+use uuid::Uuid;
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct TrashBinProps {
+    pub trash: Rc<Trash>,
+    /// Puts the deleted task back in the column it was deleted from.
+    pub onrestore: Callback<Uuid>,
+    pub onempty: Callback<MouseEvent>,
+    pub onretention: Callback<MouseEvent>,
+    pub onclose: Callback<MouseEvent>,
+}
+
+#[function_component(TrashBin)]
+pub fn trash_bin(props: &TrashBinProps) -> Html {
+    let tasks = props.trash.tasks().collect::<Vec<_>>();
+
+    html! {
+        <div class="burn-history trash-bin">
+            <div class="burn-history-header">
+                <div>{"ゴミ箱"}</div>
+                <button class="burn-history-close" onclick={props.onclose.clone()}>{"×"}</button>
+            </div>
+            <div class="trash-bin-settings">
+                <span>{ format!("削除したタスクは {} 日間保存されます", props.trash.retention_days()) }</span>
+                <button onclick={props.onretention.clone()}>{"変更"}</button>
+                <button onclick={props.onempty.clone()} disabled={tasks.is_empty()}>
+                    {"空にする"}
+                </button>
+            </div>
+            <div class="burn-history-pages">
+                if tasks.is_empty() {
+                    <div class="burn-history-empty">{"ゴミ箱は空です"}</div>
+                }
+                { for tasks.iter().map(|deleted| restorable_task(deleted, &props.onrestore)) }
+            </div>
+        </div>
+    }
+}
+
+fn restorable_task(deleted: &DeletedTask, onrestore: &Callback<Uuid>) -> Html {
+    let task = &deleted.task;
+    let onclick = {
+        let onrestore = onrestore.clone();
+        let id = task.id();
+        Callback::from(move |_: MouseEvent| onrestore.emit(id))
+    };
+
+    html! {
+        <div class="burn-page-task" key={task.id().to_string()}>
+            <div class="burn-page-date">{ format_timestamp(deleted.deleted_at) }</div>
+            <p class="card">{ task_value_as_html(&task.value, task.done) }</p>
+            <div class="burn-page-restore-buttons">
+                <button class="burn-page-restore" {onclick}>
+                    { format!("{} に戻す", task.rank) }
+                </button>
+            </div>
+        </div>
+    }
+}